@@ -0,0 +1,259 @@
+use anyhow::{Context, Result, anyhow};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::script_generator::SRS_FILE;
+
+/// Proof type passed to `ezkl prove` for leaves that will be folded into an
+/// aggregated SNARK. `for-aggr` makes EZKL emit a single KZG commitment
+/// instead of full calldata, which is what `ezkl aggregate` expects as input.
+pub const AGGR_PROOF_TYPE: &str = "for-aggr";
+
+fn log_status(message: &str) {
+    println!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+fn log_success(message: &str) {
+    println!("[SUCCESS] {}", message.green());
+}
+
+fn log_error(message: &str) {
+    eprintln!("[ERROR] {}", message.red());
+}
+
+/// A single address's leaf proof, generated in aggregation-friendly mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeafProof {
+    pub address: String,
+    pub proof_path: PathBuf,
+    pub settings_path: PathBuf,
+}
+
+/// The result of folding every leaf proof into one recursive SNARK.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedProof {
+    pub aggr_proof_path: PathBuf,
+    pub aggr_vk_path: PathBuf,
+    /// Each address's ordinal position among the leaves, in submission
+    /// order. See [`LeafIndexEntry::instance_index`] for converting this to
+    /// an actual slot in the aggregated public instance vector.
+    pub leaf_indices: Vec<LeafIndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeafIndexEntry {
+    pub address: String,
+    /// This leaf's position among the leaves folded into the aggregated
+    /// SNARK (`0..leaves.len()`), *not* its index within the aggregated
+    /// instance vector itself. The real instance vector is prefixed by the
+    /// KZG accumulator limbs `ezkl aggregate` emits, whose count depends on
+    /// the aggregation circuit's accumulator encoding (point count × limbs
+    /// per coordinate) and isn't something this module can know since it
+    /// only shells out to the `ezkl` CLI rather than linking the circuit
+    /// that produces it. Use [`LeafIndexEntry::instance_index`] with that
+    /// limb count, read from the aggregation circuit's own settings, to get
+    /// the real offset.
+    pub leaf_index: usize,
+}
+
+impl LeafIndexEntry {
+    /// Converts this leaf's ordinal into its real slot within the aggregated
+    /// instance vector, given the number of field-element limbs the KZG
+    /// accumulator occupies at the front of that vector.
+    pub fn instance_index(&self, accumulator_limb_count: usize) -> usize {
+        accumulator_limb_count + self.leaf_index
+    }
+}
+
+/// Generates a leaf proof for `address` using the `for-aggr` proof type so it
+/// can later be folded by [`aggregate_leaf_proofs`]. This mirrors the `prove`
+/// stage of `script_generator::run_ezkl_pipeline`, but calls `ezkl prove`
+/// directly since aggregation needs control over the `--proof-type` flag.
+pub fn generate_leaf_proof(
+    address: &str,
+    address_dir: &str,
+    compiled_circuit: &Path,
+    pk_path: &Path,
+    srs_path: &Path,
+    settings_path: &Path,
+) -> Result<LeafProof> {
+    log_status(&format!("Generating aggregation-friendly leaf proof for {}", address));
+
+    let ezkl_bin = which::which("ezkl")
+        .map_err(|_| anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl"))?;
+
+    let witness_path = Path::new(address_dir).join("witness.json");
+    let proof_path = Path::new(address_dir).join("proof.json");
+
+    let output = Command::new(&ezkl_bin)
+        .arg("prove")
+        .arg("--witness")
+        .arg(&witness_path)
+        .arg("--compiled-circuit")
+        .arg(compiled_circuit)
+        .arg("--pk-path")
+        .arg(pk_path)
+        .arg("--srs-path")
+        .arg(srs_path)
+        .arg("--proof-path")
+        .arg(&proof_path)
+        .arg("--proof-type")
+        .arg(AGGR_PROOF_TYPE)
+        .output()
+        .context("Failed to execute EZKL prove command in aggregation mode")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log_error(&format!("Failed to generate leaf proof for {}: {}", address, stderr));
+        return Err(anyhow!("Failed to generate leaf proof for {}: {}", address, stderr));
+    }
+
+    log_success(&format!("Leaf proof generated for {}", address));
+
+    Ok(LeafProof {
+        address: address.to_string(),
+        proof_path,
+        settings_path: settings_path.to_path_buf(),
+    })
+}
+
+/// Runs `ezkl setup-aggregate` followed by `ezkl aggregate` over every leaf
+/// proof, producing one `aggr.proof` and `aggr_vk.key`. All leaves must share
+/// the SRS used by `shared_circuit/` (enforced by the native pipeline
+/// already generating one circuit for every address), and the aggregation
+/// SRS degree must be at least as large as the leaves' logrows.
+pub fn aggregate_leaf_proofs(
+    leaves: &[LeafProof],
+    aggregation_dir: &str,
+    aggregation_srs_path: &Path,
+) -> Result<AggregatedProof> {
+    if leaves.is_empty() {
+        return Err(anyhow!("Cannot aggregate an empty set of leaf proofs"));
+    }
+
+    log_status(&format!("Aggregating {} leaf proofs", leaves.len()));
+    fs::create_dir_all(aggregation_dir)
+        .with_context(|| format!("Failed to create aggregation directory '{}'", aggregation_dir))?;
+
+    let ezkl_bin = which::which("ezkl")
+        .map_err(|_| anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl"))?;
+
+    let aggr_pk_path = Path::new(aggregation_dir).join("aggr_pk.key");
+    let aggr_vk_path = Path::new(aggregation_dir).join("aggr_vk.key");
+    let aggr_proof_path = Path::new(aggregation_dir).join("aggr.proof");
+
+    let proof_paths: Vec<&Path> = leaves.iter().map(|leaf| leaf.proof_path.as_path()).collect();
+
+    let setup_status = Command::new(&ezkl_bin)
+        .arg("setup-aggregate")
+        .arg("--sample-snarks")
+        .args(&proof_paths)
+        .arg("--vk-path")
+        .arg(&aggr_vk_path)
+        .arg("--pk-path")
+        .arg(&aggr_pk_path)
+        .arg("--srs-path")
+        .arg(aggregation_srs_path)
+        .status()
+        .context("Failed to execute EZKL setup-aggregate command")?;
+
+    if !setup_status.success() {
+        return Err(anyhow!("EZKL setup-aggregate failed with status: {}", setup_status));
+    }
+
+    let aggregate_status = Command::new(&ezkl_bin)
+        .arg("aggregate")
+        .arg("--aggregation-snarks")
+        .args(&proof_paths)
+        .arg("--pk-path")
+        .arg(&aggr_pk_path)
+        .arg("--proof-path")
+        .arg(&aggr_proof_path)
+        .arg("--srs-path")
+        .arg(aggregation_srs_path)
+        .status()
+        .context("Failed to execute EZKL aggregate command")?;
+
+    if !aggregate_status.success() {
+        return Err(anyhow!("EZKL aggregate failed with status: {}", aggregate_status));
+    }
+
+    log_success("Aggregated proof generated successfully");
+
+    // Record each leaf's ordinal in submission order; see
+    // `LeafIndexEntry::instance_index` for turning this into the real slot
+    // within the aggregated instance vector, which is prefixed by the KZG
+    // accumulator limbs.
+    let leaf_indices = leaves
+        .iter()
+        .enumerate()
+        .map(|(leaf_index, leaf)| LeafIndexEntry {
+            address: leaf.address.clone(),
+            leaf_index,
+        })
+        .collect();
+
+    Ok(AggregatedProof {
+        aggr_proof_path,
+        aggr_vk_path,
+        leaf_indices,
+    })
+}
+
+/// Renders `AggregatedVerifier.sol`, the single on-chain verifier that every
+/// tier's leaf proof is checked against via `aggr.proof`.
+pub fn create_aggregated_verifier(
+    aggregated: &AggregatedProof,
+    aggregation_srs_path: &Path,
+    sol_output_path: &Path,
+) -> Result<()> {
+    log_status("Generating aggregated Solidity verifier");
+
+    let ezkl_bin = which::which("ezkl")
+        .map_err(|_| anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl"))?;
+
+    let status = Command::new(&ezkl_bin)
+        .arg("create-evm-verifier-aggr")
+        .arg("--vk-path")
+        .arg(&aggregated.aggr_vk_path)
+        .arg("--sol-code-path")
+        .arg(sol_output_path)
+        .arg("--srs-path")
+        .arg(aggregation_srs_path)
+        .status()
+        .context("Failed to execute EZKL create-evm-verifier-aggr command")?;
+
+    if !status.success() {
+        return Err(anyhow!("EZKL create-evm-verifier-aggr failed with status: {}", status));
+    }
+
+    log_success(&format!(
+        "Aggregated verifier written to {}",
+        sol_output_path.display()
+    ));
+
+    Ok(())
+}
+
+/// Records, for every address in the aggregated batch, its ordinal position
+/// among the folded leaves (see [`LeafIndexEntry`] for converting that to a
+/// real instance-vector slot). This is written alongside the per-address
+/// `proof_registry/<address>.json` entries so a verifier can recover
+/// individual scores post-aggregation.
+pub fn record_aggregation_indices(aggregated: &AggregatedProof, registry_dir: &str) -> Result<()> {
+    fs::create_dir_all(registry_dir)?;
+    let index_path = Path::new(registry_dir).join("aggregation_index.json");
+    fs::write(&index_path, serde_json::to_string_pretty(&aggregated.leaf_indices)?)
+        .with_context(|| format!("Failed to write aggregation index to {}", index_path.display()))?;
+    log_success(&format!("Aggregation index written to {}", index_path.display()));
+    Ok(())
+}
+
+/// Default shared SRS file used for leaf proofs; aggregation re-uses it
+/// unless a larger-degree SRS is explicitly supplied via `aggregation_srs_path`.
+pub fn default_srs_path(proof_gen_dir: &str) -> PathBuf {
+    Path::new(proof_gen_dir).join(SRS_FILE)
+}
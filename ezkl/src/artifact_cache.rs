@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const KEY_FILE_NAME: &str = "artifact_cache.key";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Per-install BLAKE3 key so a cached digest can't be forged by anyone who
+/// only knows the (public) hash algorithm. Generated once on first use and
+/// persisted in the OS config directory (next to the default `config.toml`,
+/// even when another config file is given), so every process on this
+/// machine shares the same key.
+fn install_key() -> Result<[u8; 32]> {
+    let path = crate::config::default_config_dir().join(KEY_FILE_NAME);
+
+    if let Ok(existing) = fs::read(&path) {
+        if existing.len() == 32 {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&existing);
+            return Ok(key);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let seed = format!("{:?}-{}", std::time::SystemTime::now(), std::process::id());
+    let key = *blake3::hash(seed.as_bytes()).as_bytes();
+    fs::write(&path, key)
+        .with_context(|| format!("Failed to persist artifact cache key to {}", path.display()))?;
+    Ok(key)
+}
+
+/// Keyed BLAKE3 digest of `data`, hex-encoded.
+pub fn digest_bytes(data: &[u8]) -> Result<String> {
+    let key = install_key()?;
+    Ok(blake3::keyed_hash(&key, data).to_hex().to_string())
+}
+
+/// Keyed BLAKE3 digest of a file's contents, hex-encoded.
+pub fn digest_file(path: &Path) -> Result<String> {
+    let data = fs::read(path)
+        .with_context(|| format!("Failed to read {} for digesting", path.display()))?;
+    digest_bytes(&data)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StageRecord {
+    inputs_digest: String,
+    output_digest: String,
+}
+
+/// Tracks, per pipeline stage, the digest of the inputs that produced its
+/// output artifact and the digest of that artifact itself. Keying on actual
+/// input content (rather than just "does the output file exist") means a
+/// stage is only skipped when its real inputs haven't changed, and the
+/// output digest check catches tampering or a truncated/partial download.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    stages: HashMap<String, StageRecord>,
+}
+
+impl Manifest {
+    fn path(proof_gen_dir: &Path) -> PathBuf {
+        proof_gen_dir.join(MANIFEST_FILE_NAME)
+    }
+
+    pub fn load(proof_gen_dir: &Path) -> Result<Self> {
+        let path = Self::path(proof_gen_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read manifest at {}", path.display()))?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn save(&self, proof_gen_dir: &Path) -> Result<()> {
+        let path = Self::path(proof_gen_dir);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write manifest to {}", path.display()))
+    }
+
+    /// True if `stage` was already run with these exact inputs and its
+    /// recorded output artifact still matches the digest taken at the time,
+    /// so regenerating it can be skipped.
+    pub fn is_fresh(&self, stage: &str, inputs_digest: &str, output_path: &Path) -> bool {
+        let Some(record) = self.stages.get(stage) else {
+            return false;
+        };
+        if record.inputs_digest != inputs_digest {
+            return false;
+        }
+        matches!(digest_file(output_path), Ok(actual) if actual == record.output_digest)
+    }
+
+    /// The digest recorded for `stage`'s output artifact the last time it
+    /// ran, used to feed downstream stages whose inputs are "stage X's
+    /// output" rather than the raw file bytes again.
+    pub fn output_digest(&self, stage: &str) -> Option<&str> {
+        self.stages.get(stage).map(|r| r.output_digest.as_str())
+    }
+
+    /// Records `stage`'s input digest and digests `output_path` to capture
+    /// its current output state.
+    pub fn record(&mut self, stage: &str, inputs_digest: &str, output_path: &Path) -> Result<()> {
+        let output_digest = digest_file(output_path)?;
+        self.stages.insert(
+            stage.to_string(),
+            StageRecord {
+                inputs_digest: inputs_digest.to_string(),
+                output_digest,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_output_file(contents: &[u8]) -> PathBuf {
+        let n = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let mut path = std::env::temp_dir();
+        path.push(format!("ezkl_manifest_test_{}_{}.bin", std::process::id(), n));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn is_fresh_is_false_for_an_unrecorded_stage() {
+        let manifest = Manifest::default();
+        let output_path = temp_output_file(b"output");
+
+        let fresh = manifest.is_fresh("compile-circuit", "inputs-digest", &output_path);
+
+        fs::remove_file(&output_path).ok();
+        assert!(!fresh);
+    }
+
+    #[test]
+    fn is_fresh_is_false_when_inputs_digest_changed() {
+        let mut manifest = Manifest::default();
+        let output_path = temp_output_file(b"output");
+        manifest.record("compile-circuit", "old-inputs", &output_path).unwrap();
+
+        let fresh = manifest.is_fresh("compile-circuit", "new-inputs", &output_path);
+
+        fs::remove_file(&output_path).ok();
+        assert!(!fresh);
+    }
+
+    #[test]
+    fn is_fresh_is_false_when_output_file_changed_since_recording() {
+        let mut manifest = Manifest::default();
+        let output_path = temp_output_file(b"original output");
+        manifest.record("compile-circuit", "inputs-digest", &output_path).unwrap();
+
+        fs::write(&output_path, b"tampered output").unwrap();
+        let fresh = manifest.is_fresh("compile-circuit", "inputs-digest", &output_path);
+
+        fs::remove_file(&output_path).ok();
+        assert!(!fresh);
+    }
+
+    #[test]
+    fn is_fresh_is_true_when_inputs_and_output_are_unchanged() {
+        let mut manifest = Manifest::default();
+        let output_path = temp_output_file(b"output");
+        manifest.record("compile-circuit", "inputs-digest", &output_path).unwrap();
+
+        let fresh = manifest.is_fresh("compile-circuit", "inputs-digest", &output_path);
+
+        fs::remove_file(&output_path).ok();
+        assert!(fresh);
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::artifact_cache::digest_file;
+
+const REGISTRY_FILE: &str = "registry.json";
+const MIGRATIONS_FILE: &str = "migrations.json";
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// A single generated circuit, pinned to the model/settings that produced
+/// it. Past versions' proving/verifying keys are kept under
+/// `circuits/<version>/` so a proof generated against an older circuit can
+/// still be checked after the shared circuit has moved on to a new model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitVersion {
+    pub version: u32,
+    pub model_digest: String,
+    pub settings_digest: String,
+    pub vk_digest: String,
+    /// EZKL doesn't track feature names, so columns are identified
+    /// positionally (`col_0`, `col_1`, ...) by their index into
+    /// `model_input_scales`.
+    pub feature_columns: Vec<String>,
+    pub num_instances: usize,
+    pub created_at: u64,
+}
+
+fn registry_path(circuits_dir: &Path) -> PathBuf {
+    circuits_dir.join(REGISTRY_FILE)
+}
+
+fn migrations_path(circuits_dir: &Path) -> PathBuf {
+    circuits_dir.join(MIGRATIONS_FILE)
+}
+
+pub fn load_registry(circuits_dir: &Path) -> Result<Vec<CircuitVersion>> {
+    let path = registry_path(circuits_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read circuit registry at {}", path.display()))?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn save_registry(circuits_dir: &Path, versions: &[CircuitVersion]) -> Result<()> {
+    fs::create_dir_all(circuits_dir)?;
+    let path = registry_path(circuits_dir);
+    fs::write(&path, serde_json::to_string_pretty(versions)?)
+        .with_context(|| format!("Failed to write circuit registry to {}", path.display()))
+}
+
+/// Looks up a previously registered circuit version, failing loudly if it
+/// was never recorded (e.g. a typo'd version number) rather than silently
+/// treating it as version 0.
+pub fn get_version(circuits_dir: &Path, version: u32) -> Result<CircuitVersion> {
+    load_registry(circuits_dir)?
+        .into_iter()
+        .find(|v| v.version == version)
+        .ok_or_else(|| anyhow!("No circuit version {} recorded under {}", version, circuits_dir.display()))
+}
+
+fn read_settings_value(settings_path: &Path) -> Result<serde_json::Value> {
+    let data = fs::read_to_string(settings_path)
+        .with_context(|| format!("Failed to read settings at {}", settings_path.display()))?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn read_feature_columns(settings: &serde_json::Value) -> Vec<String> {
+    let count = settings
+        .get("model_input_scales")
+        .and_then(|v| v.as_array())
+        .map(|a| a.len())
+        .unwrap_or(0);
+    (0..count).map(|i| format!("col_{}", i)).collect()
+}
+
+fn read_num_instances(settings: &serde_json::Value) -> usize {
+    settings
+        .get("model_output_scales")
+        .and_then(|v| v.as_array())
+        .map(|a| a.len())
+        .unwrap_or(0)
+}
+
+/// Stamps the circuit currently in `shared_circuit/` as the next monotonic
+/// version: copies its settings/compiled-circuit/proving/verifying keys into
+/// `circuits/<version>/` and appends an entry to `circuits/registry.json`.
+/// A no-op (returning the existing entry) if the settings digest matches
+/// the most recently registered version, so reusing the same circuit across
+/// addresses doesn't mint a new version each time.
+pub fn register_circuit(
+    circuits_dir: &Path,
+    model_path: &Path,
+    settings_path: &Path,
+    compiled_circuit_path: &Path,
+    pk_path: &Path,
+    vk_path: &Path,
+) -> Result<CircuitVersion> {
+    let mut versions = load_registry(circuits_dir)?;
+    let settings_digest = digest_file(settings_path)?;
+
+    if let Some(latest) = versions.last() {
+        if latest.settings_digest == settings_digest {
+            return Ok(latest.clone());
+        }
+    }
+
+    let next_version = versions.iter().map(|v| v.version).max().unwrap_or(0) + 1;
+    let version_dir = circuits_dir.join(next_version.to_string());
+    fs::create_dir_all(&version_dir)?;
+
+    fs::copy(settings_path, version_dir.join("settings.json"))
+        .context("Failed to archive settings.json for circuit version")?;
+    fs::copy(compiled_circuit_path, version_dir.join("model.compiled"))
+        .context("Failed to archive model.compiled for circuit version")?;
+    fs::copy(pk_path, version_dir.join("pk.key"))
+        .context("Failed to archive pk.key for circuit version")?;
+    fs::copy(vk_path, version_dir.join("vk.key"))
+        .context("Failed to archive vk.key for circuit version")?;
+
+    let settings = read_settings_value(settings_path)?;
+    let entry = CircuitVersion {
+        version: next_version,
+        model_digest: digest_file(model_path)?,
+        settings_digest,
+        vk_digest: digest_file(vk_path)?,
+        feature_columns: read_feature_columns(&settings),
+        num_instances: read_num_instances(&settings),
+        created_at: now_secs(),
+    };
+
+    versions.push(entry.clone());
+    save_registry(circuits_dir, &versions)?;
+    Ok(entry)
+}
+
+/// Describes what changes between two registered circuit versions and
+/// whether a deployment can keep its existing on-chain verifier, analogous
+/// to a release-upgrade ("relup") descriptor between two release versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradePlan {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changed_feature_columns: Vec<String>,
+    pub public_input_layout_changed: bool,
+    pub old_proofs_verifiable: bool,
+}
+
+/// Diffs two registered circuit versions without changing anything on disk,
+/// so a deployment can inspect the plan before committing to [`apply_upgrade`].
+pub fn plan_upgrade(circuits_dir: &Path, from_version: u32, to_version: u32) -> Result<UpgradePlan> {
+    let from = get_version(circuits_dir, from_version)?;
+    let to = get_version(circuits_dir, to_version)?;
+
+    let max_len = from.feature_columns.len().max(to.feature_columns.len());
+    let changed_feature_columns: Vec<String> = (0..max_len)
+        .filter_map(|i| {
+            let old = from.feature_columns.get(i);
+            let new = to.feature_columns.get(i);
+            if old != new {
+                new.or(old).cloned()
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(UpgradePlan {
+        from_version,
+        to_version,
+        changed_feature_columns,
+        public_input_layout_changed: from.num_instances != to.num_instances,
+        // A verifying key change means the verification equation itself
+        // changed, so proofs produced under the old vk no longer check out
+        // under the new one, even if the public-input layout is unchanged.
+        old_proofs_verifiable: from.vk_digest == to.vk_digest,
+    })
+}
+
+fn load_migrations(circuits_dir: &Path) -> Result<Vec<UpgradePlan>> {
+    let path = migrations_path(circuits_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read migration log at {}", path.display()))?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn save_migrations(circuits_dir: &Path, migrations: &[UpgradePlan]) -> Result<()> {
+    let path = migrations_path(circuits_dir);
+    fs::write(&path, serde_json::to_string_pretty(migrations)?)
+        .with_context(|| format!("Failed to write migration log to {}", path.display()))
+}
+
+/// Promotes `plan.to_version`'s compiled circuit, settings, and
+/// proving/verifying keys into the live `shared_circuit/` directory (plus
+/// `settings.json` one level up, alongside it) and appends the plan to
+/// `circuits/migrations.json`, giving deployments a durable record of when
+/// a transition happened and whether it requires redeploying the on-chain
+/// verifier. Restoring all four artifacts together (not just the keys)
+/// keeps the live circuit self-consistent after a rollback, rather than
+/// pairing a restored verifying key with whatever compiled circuit/settings
+/// happened to still be on disk.
+pub fn apply_upgrade(plan: &UpgradePlan, circuits_dir: &Path, shared_circuit_dir: &Path) -> Result<()> {
+    let to_dir = circuits_dir.join(plan.to_version.to_string());
+    fs::create_dir_all(shared_circuit_dir)?;
+
+    // `settings.json` lives one level above `shared_circuit/` itself, per
+    // `pipeline::Context::new`.
+    let settings_path = shared_circuit_dir
+        .parent()
+        .map(|p| p.join("settings.json"))
+        .unwrap_or_else(|| shared_circuit_dir.join("settings.json"));
+
+    fs::copy(to_dir.join("settings.json"), &settings_path)
+        .context("Failed to restore settings.json for circuit upgrade")?;
+    fs::copy(to_dir.join("model.compiled"), shared_circuit_dir.join("model.compiled"))
+        .context("Failed to restore model.compiled for circuit upgrade")?;
+    fs::copy(to_dir.join("pk.key"), shared_circuit_dir.join("pk.key"))
+        .context("Failed to promote pk.key for circuit upgrade")?;
+    fs::copy(to_dir.join("vk.key"), shared_circuit_dir.join("vk.key"))
+        .context("Failed to promote vk.key for circuit upgrade")?;
+
+    // Any rendered EVM verifier under `shared_circuit/contract/` was minted
+    // against the vk.key we just replaced; remove it so `CreateEvmVerifier`'s
+    // `is_complete` check doesn't treat it as still valid for the restored
+    // version and re-renders it on the next run instead.
+    let contract_dir = shared_circuit_dir.join(crate::pipeline::context::CONTRACT_DIR_NAME);
+    if contract_dir.exists() {
+        fs::remove_dir_all(&contract_dir)
+            .context("Failed to invalidate stale EVM verifier contract for circuit upgrade")?;
+    }
+
+    let mut migrations = load_migrations(circuits_dir)?;
+    migrations.push(plan.clone());
+    save_migrations(circuits_dir, &migrations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_circuits_dir() -> PathBuf {
+        let n = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let mut path = std::env::temp_dir();
+        path.push(format!("ezkl_circuit_registry_test_{}_{}", std::process::id(), n));
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    fn version(version: u32, feature_columns: &[&str], num_instances: usize, vk_digest: &str) -> CircuitVersion {
+        CircuitVersion {
+            version,
+            model_digest: format!("model-{}", version),
+            settings_digest: format!("settings-{}", version),
+            vk_digest: vk_digest.to_string(),
+            feature_columns: feature_columns.iter().map(|s| s.to_string()).collect(),
+            num_instances,
+            created_at: 0,
+        }
+    }
+
+    fn seed_registry(circuits_dir: &Path, versions: &[CircuitVersion]) {
+        save_registry(circuits_dir, versions).unwrap();
+    }
+
+    #[test]
+    fn plan_upgrade_detects_no_changes() {
+        let dir = temp_circuits_dir();
+        seed_registry(
+            &dir,
+            &[
+                version(1, &["col_0", "col_1"], 1, "vk-a"),
+                version(2, &["col_0", "col_1"], 1, "vk-a"),
+            ],
+        );
+
+        let plan = plan_upgrade(&dir, 1, 2).unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+        assert!(plan.changed_feature_columns.is_empty());
+        assert!(!plan.public_input_layout_changed);
+        assert!(plan.old_proofs_verifiable);
+    }
+
+    #[test]
+    fn plan_upgrade_detects_added_feature_column_and_vk_change() {
+        let dir = temp_circuits_dir();
+        seed_registry(
+            &dir,
+            &[
+                version(1, &["col_0"], 1, "vk-a"),
+                version(2, &["col_0", "col_1"], 1, "vk-b"),
+            ],
+        );
+
+        let plan = plan_upgrade(&dir, 1, 2).unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(plan.changed_feature_columns, vec!["col_1".to_string()]);
+        assert!(!plan.old_proofs_verifiable);
+    }
+
+    #[test]
+    fn plan_upgrade_detects_public_input_layout_change() {
+        let dir = temp_circuits_dir();
+        seed_registry(
+            &dir,
+            &[
+                version(1, &["col_0"], 1, "vk-a"),
+                version(2, &["col_0"], 2, "vk-a"),
+            ],
+        );
+
+        let plan = plan_upgrade(&dir, 1, 2).unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+        assert!(plan.public_input_layout_changed);
+    }
+
+    #[test]
+    fn plan_upgrade_fails_for_an_unknown_version() {
+        let dir = temp_circuits_dir();
+        seed_registry(&dir, &[version(1, &["col_0"], 1, "vk-a")]);
+
+        let result = plan_upgrade(&dir, 1, 2);
+
+        fs::remove_dir_all(&dir).ok();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const QUALIFIER: &str = "";
+const ORGANIZATION: &str = "Loan-ZKML";
+const APPLICATION: &str = "ezkl-ml";
+
+const DEFAULT_EZKL_BINARY: &str = "ezkl";
+const DEFAULT_SRS_SOURCE_URL: &str = "https://trusted-setup.ezkl.xyz";
+const DEFAULT_GENERATE_EVM_VERIFIER: bool = true;
+
+/// Resolved runtime configuration for the proving pipeline: where EZKL lives,
+/// where proof artifacts are written, and whether to render an EVM verifier.
+/// Resolved once via [`resolve`] from three layered sources (lowest to
+/// highest priority): built-in defaults, `config.toml`, then environment
+/// variables, so a user can override any of it without editing code.
+/// The SRS download in [`crate::srs`] reads none of these fields: it runs
+/// the `ezkl` found on `PATH`, does not pass `srs_source_url`, and keeps its
+/// cache under [`default_data_dir`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EzklConfig {
+    pub ezkl_binary: PathBuf,
+    pub srs_source_url: String,
+    pub proof_output_dir: PathBuf,
+    pub generate_evm_verifier: bool,
+}
+
+impl Default for EzklConfig {
+    fn default() -> Self {
+        Self {
+            ezkl_binary: PathBuf::from(DEFAULT_EZKL_BINARY),
+            srs_source_url: DEFAULT_SRS_SOURCE_URL.to_string(),
+            proof_output_dir: default_data_dir().join("proof_generation"),
+            generate_evm_verifier: DEFAULT_GENERATE_EVM_VERIFIER,
+        }
+    }
+}
+
+/// Partial overrides read from `config.toml`; every field is optional so the
+/// file only needs to mention what it's overriding.
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    ezkl_binary: Option<PathBuf>,
+    srs_source_url: Option<String>,
+    proof_output_dir: Option<PathBuf>,
+    generate_evm_verifier: Option<bool>,
+}
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
+}
+
+/// OS-standard data directory (proof artifacts, compiled circuits, SRS
+/// cache) for this crate, falling back to `./proof_generation`'s parent in
+/// environments with no resolvable home directory (e.g. some CI sandboxes).
+pub fn default_data_dir() -> PathBuf {
+    project_dirs()
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// OS-standard config directory holding `config.toml`.
+pub fn default_config_dir() -> PathBuf {
+    project_dirs()
+        .map(|dirs| dirs.config_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn config_file_path() -> PathBuf {
+    default_config_dir().join("config.toml")
+}
+
+/// Resolves the effective configuration by layering, in increasing priority:
+/// built-in defaults, `config.toml` in the OS config directory, then
+/// `EZKL_ML_*` environment variables.
+pub fn resolve() -> Result<EzklConfig> {
+    resolve_with_override(None)
+}
+
+/// Like [`resolve`], but reads the config file from `config_path_override`
+/// (e.g. a CLI `--config` flag) instead of the OS config directory when one
+/// is given. Environment variables still take priority over either.
+pub fn resolve_with_override(config_path_override: Option<&Path>) -> Result<EzklConfig> {
+    let mut config = EzklConfig::default();
+
+    let file_config = match config_path_override {
+        Some(path) => {
+            if !path.exists() {
+                return Err(anyhow::anyhow!("Config file not found at {}", path.display()));
+            }
+            read_file_config(path)?
+        }
+        None => read_file_config(&config_file_path())?,
+    };
+    apply_file_config(&mut config, file_config);
+    apply_env_overrides(&mut config);
+
+    Ok(config)
+}
+
+fn read_file_config(path: &Path) -> Result<FileConfig> {
+    if !path.exists() {
+        return Ok(FileConfig::default());
+    }
+
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file at {}", path.display()))?;
+    toml::from_str(&data).with_context(|| format!("Failed to parse config file at {}", path.display()))
+}
+
+fn apply_file_config(config: &mut EzklConfig, file_config: FileConfig) {
+    if let Some(ezkl_binary) = file_config.ezkl_binary {
+        config.ezkl_binary = ezkl_binary;
+    }
+    if let Some(srs_source_url) = file_config.srs_source_url {
+        config.srs_source_url = srs_source_url;
+    }
+    if let Some(proof_output_dir) = file_config.proof_output_dir {
+        config.proof_output_dir = proof_output_dir;
+    }
+    if let Some(generate_evm_verifier) = file_config.generate_evm_verifier {
+        config.generate_evm_verifier = generate_evm_verifier;
+    }
+}
+
+fn apply_env_overrides(config: &mut EzklConfig) {
+    if let Ok(value) = std::env::var("EZKL_ML_EZKL_BINARY") {
+        config.ezkl_binary = PathBuf::from(value);
+    }
+    if let Ok(value) = std::env::var("EZKL_ML_SRS_SOURCE_URL") {
+        config.srs_source_url = value;
+    }
+    if let Ok(value) = std::env::var("EZKL_ML_PROOF_OUTPUT_DIR") {
+        config.proof_output_dir = PathBuf::from(value);
+    }
+    if let Ok(value) = std::env::var("EZKL_ML_GENERATE_EVM_VERIFIER") {
+        if let Ok(parsed) = value.parse::<bool>() {
+            config.generate_evm_verifier = parsed;
+        }
+    }
+}
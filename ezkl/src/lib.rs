@@ -0,0 +1,12 @@
+pub mod aggregation;
+pub mod artifact_cache;
+pub mod circuit_registry;
+pub mod config;
+pub mod pipeline;
+pub mod proof_registry;
+pub mod schema;
+pub mod script_generator;
+pub mod solidity_verifier;
+pub mod srs;
+pub mod task_manager;
+pub mod utils;
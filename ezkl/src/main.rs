@@ -1,19 +1,16 @@
-mod proof_registry;
-mod script_generator;
-mod utils;
-
 use anyhow::{Result, anyhow};
 use std::path::Path;
 use std::fs;
-use std::process::Command;
 use synthetic_data::{
     generate_synthetic_data_with_test_addresses,
     save_data_as_json
 };
 
-use crate::proof_registry::create_proof_registry;
-use crate::script_generator::{initialize_shared_resources, create_address_input, create_ezkl_script, run_ezkl_process, PROOF_GEN_DIR};
-use crate::utils::{address_to_filename, get_features_for_address};
+use ezkl::config;
+use ezkl::proof_registry::create_proof_registry;
+use ezkl::script_generator::{initialize_shared_resources, create_address_input, run_ezkl_pipeline, SRS_FILE};
+use ezkl::solidity_verifier::{read_constraint_system_meta, render_verifier, encode_calldata};
+use ezkl::utils::{address_to_filename, get_features_for_address};
 
 const CONTRACTS_SRC_PATH: &str = "../../contracts/src";
 const CONTRACTS_SCRIPT_PATH: &str = "../../contracts/script";
@@ -24,14 +21,16 @@ const MEDIUM_TIER_ADDRESS: &str = "0x276ef71c8F12508d187E7D8Fcc2FE6A38a5884B1";
 const HIGH_TIER_ADDRESS: &str = "0x4444444444444444444444444444444444444444";
 
 fn main() -> Result<()> {
+    let config = config::resolve()?;
+
     // Create directories for artifacts
-    fs::create_dir_all(PROOF_GEN_DIR)?;
+    fs::create_dir_all(&config.proof_output_dir)?;
     fs::create_dir_all("script")?;
     fs::create_dir_all("proof_registry")?;
 
     // Step 1: Generate synthetic data with test addresses
     let data = generate_synthetic_data_with_test_addresses(1000)?;
-    save_data_as_json(&data, &format!("{}/credit_data.json", PROOF_GEN_DIR))?;
+    save_data_as_json(&data, &format!("{}/credit_data.json", config.proof_output_dir.display()))?;
     println!("[SUCCESS] Common EZKL setup completed successfully");
 
     // Define the addresses to generate proofs for
@@ -45,28 +44,14 @@ fn main() -> Result<()> {
     println!("Generating shared credit model...");
     let sample_address = test_addresses[0];
     let sample_features = get_features_for_address(&data, sample_address)?;
-    initialize_shared_resources(&sample_features, sample_address)?;
-
-    // Step 3: Set up common EZKL resources
-    println!("Setting up common EZKL resources...");
-    let status = Command::new("sh")
-        .arg("./run_ezkl_common.sh")
-        .arg("proof_generation/credit_model.onnx")  // model path
-        .arg("proof_generation")                    // output dir
-        .arg("proof_generation/kzg.srs")           // srs path
-        .status()?;
-
-    if !status.success() {
-        return Err(anyhow!("Failed to run common EZKL setup"));
-    }
-    println!("[SUCCESS] Common EZKL setup completed successfully");
+    initialize_shared_resources(&sample_features, sample_address, &config)?;
 
     // Step 4: Generate proofs for each test address
     for address in &test_addresses {
         println!("Generating proof for address: {}", address);
 
         // Create a subdirectory for this address
-        let address_dir = format!("{}/{}", PROOF_GEN_DIR, address_to_filename(address));
+        let address_dir = config.proof_output_dir.join(address_to_filename(address));
         fs::create_dir_all(&address_dir)?;
 
         // Get features for this address
@@ -88,45 +73,86 @@ fn main() -> Result<()> {
         println!("Credit score for address {}: {:.3} ({})", address, score, tier);
 
         // Generate input.json for this address (using the shared model)
-        create_address_input(&address_features, address, &address_dir)?;
+        create_address_input(&address_features, address, &address_dir.to_string_lossy())?;
 
         // Generate proof with EZKL
         println!("Processing with EZKL...");
-        let script_path = Path::new(&address_dir).join("run_ezkl.sh");
         let is_medium_tier = *address == MEDIUM_TIER_ADDRESS;
-        create_ezkl_script(&script_path, &address_dir, is_medium_tier)?;
-
-        // Run EZKL script using the new run_ezkl_process function
-        run_ezkl_process(&script_path)?;
+        let generate_contract = config.generate_evm_verifier && is_medium_tier;
+        run_ezkl_pipeline(&address_dir, generate_contract, &config)?;
 
         // Create proof registry entry
         println!("Creating proof registry entry...");
-        create_proof_registry(address, &address_dir)?;
+        create_proof_registry(address, &address_dir.to_string_lossy())?;
         println!("Successfully registered proof for address: {}", address);
         println!();
     }
 
-    // Step 4: Copy artifacts for medium tier address only
-    println!("Copying artifacts for Solidity tests...");
+    // Step 5: Render the Solidity verifier natively for the medium tier
+    // address, instead of copying the shell script's monolithic
+    // contract/verifier.sol. The verifying-key artifact is kept separate so
+    // redeploying a retrained model never re-deploys verifier bytecode.
+    println!("Rendering native Solidity verifier...");
     fs::create_dir_all(CONTRACTS_SRC_PATH)?;
     fs::create_dir_all(CONTRACTS_SCRIPT_PATH)?;
 
-    let medium_dir = format!("{}/{}", PROOF_GEN_DIR, address_to_filename(MEDIUM_TIER_ADDRESS));
-    fs::copy(
-        format!("{}/contract/verifier.sol", medium_dir),
-        format!("{}/Halo2Verifier.sol", CONTRACTS_SRC_PATH)
+    let medium_dir = config.proof_output_dir.join(address_to_filename(MEDIUM_TIER_ADDRESS));
+    let shared_settings_path = config.proof_output_dir.join("settings.json");
+    let shared_vk_path = config.proof_output_dir.join("shared_circuit").join("vk.key");
+    let shared_srs_path = config.proof_output_dir.join(SRS_FILE);
+
+    let cs_meta = read_constraint_system_meta(&shared_settings_path)?;
+    render_verifier(
+        &cs_meta,
+        &shared_vk_path,
+        &shared_srs_path,
+        1,
+        Path::new(CONTRACTS_SRC_PATH),
     )?;
 
-    fs::copy(
-        format!("{}/contract/calldata.json", medium_dir),
-        format!("{}/calldata.json", CONTRACTS_SCRIPT_PATH)
+    let proof_path = medium_dir.join("proof.json");
+    let proof_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&proof_path)?)?;
+    let proof_bytes = hex::decode(
+        proof_json["proof"]
+            .as_str()
+            .ok_or_else(|| anyhow!("proof.json is missing a hex-encoded 'proof' field"))?
+            .trim_start_matches("0x"),
+    )?;
+    let instances: Vec<Vec<[u8; 32]>> = proof_json["instances"]
+        .as_array()
+        .ok_or_else(|| anyhow!("proof.json is missing an 'instances' array"))?
+        .iter()
+        .map(|column| {
+            column
+                .as_array()
+                .ok_or_else(|| anyhow!("proof.json instances entry is not an array"))?
+                .iter()
+                .map(|limb| {
+                    let bytes = hex::decode(
+                        limb.as_str()
+                            .ok_or_else(|| anyhow!("instance limb is not a hex string"))?
+                            .trim_start_matches("0x"),
+                    )?;
+                    let mut limb_bytes = [0u8; 32];
+                    let len = bytes.len().min(32);
+                    limb_bytes[..len].copy_from_slice(&bytes[..len]);
+                    Ok(limb_bytes)
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let calldata = encode_calldata(&proof_bytes, &instances)?;
+    fs::write(
+        format!("{}/calldata.json", CONTRACTS_SCRIPT_PATH),
+        serde_json::to_string_pretty(&serde_json::json!({ "calldata": format!("0x{}", hex::encode(calldata)) }))?,
     )?;
 
     println!("Proof generation complete!");
     println!("Generated artifacts:");
-    println!(" - Shared credit model in {}/", PROOF_GEN_DIR);
-    println!(" - Proofs for each address in {}/address_dir/", PROOF_GEN_DIR);
+    println!(" - Shared credit model in {}/", config.proof_output_dir.display());
+    println!(" - Proofs for each address in {}/address_dir/", config.proof_output_dir.display());
     println!(" - Medium tier address artifacts copied to contracts repo");
 
     Ok(())
-}
\ No newline at end of file
+}
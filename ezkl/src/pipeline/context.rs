@@ -0,0 +1,81 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crate::config::EzklConfig;
+use super::utils::resolve_ezkl_binary;
+
+/// Name of the subdirectory under `shared_circuit/` that holds the rendered
+/// EVM verifier contract. Shared with `circuit_registry::apply_upgrade`,
+/// which needs to invalidate it on rollback without duplicating this path.
+pub const CONTRACT_DIR_NAME: &str = "contract";
+
+/// Paths every pipeline step needs, resolved once up front rather than each
+/// step re-deriving them. Mirrors the `shared_circuit/` layout described in
+/// `script_generator`: the compiled circuit and proving/verifying keys live
+/// there and are shared across addresses, while witness/proof artifacts are
+/// per-address.
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub model_path: PathBuf,
+    pub settings_path: PathBuf,
+    pub srs_path: PathBuf,
+    pub shared_circuit_dir: PathBuf,
+    pub compiled_circuit_path: PathBuf,
+    pub pk_path: PathBuf,
+    pub vk_path: PathBuf,
+    pub address_dir: PathBuf,
+    pub input_path: PathBuf,
+    pub witness_path: PathBuf,
+    pub proof_path: PathBuf,
+    pub generate_contract: bool,
+    pub contract_dir: PathBuf,
+    pub ezkl_binary: PathBuf,
+    /// Checked by [`super::utils::run_command`] while a step's `ezkl` child
+    /// is running, so a caller can actually kill it mid-step. Defaults to a
+    /// flag that's never set; callers that need cancellation (the async
+    /// task manager) swap in their own via [`Context::with_cancellation`].
+    pub cancelled: Arc<AtomicBool>,
+}
+
+impl Context {
+    pub fn new(
+        config: &EzklConfig,
+        address_dir: &Path,
+        model_path: &Path,
+        srs_path: &Path,
+        generate_contract: bool,
+    ) -> anyhow::Result<Self> {
+        let proof_gen_dir = &config.proof_output_dir;
+        let shared_circuit_dir = proof_gen_dir.join("shared_circuit");
+        Ok(Self {
+            model_path: model_path.to_path_buf(),
+            settings_path: proof_gen_dir.join("settings.json"),
+            srs_path: srs_path.to_path_buf(),
+            compiled_circuit_path: shared_circuit_dir.join("model.compiled"),
+            pk_path: shared_circuit_dir.join("pk.key"),
+            vk_path: shared_circuit_dir.join("vk.key"),
+            // The rendered verifier only depends on the shared vk/SRS, so it
+            // lives alongside them rather than under `address_dir` — that way
+            // it's generated once per circuit version instead of once per
+            // proving request.
+            contract_dir: shared_circuit_dir.join(CONTRACT_DIR_NAME),
+            shared_circuit_dir,
+            address_dir: address_dir.to_path_buf(),
+            input_path: address_dir.join("input.json"),
+            witness_path: address_dir.join("witness.json"),
+            proof_path: address_dir.join("proof.json"),
+            generate_contract,
+            ezkl_binary: resolve_ezkl_binary(&config.ezkl_binary)?,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Swaps in a shared cancellation flag so steps run through this
+    /// context can be killed mid-flight by whoever holds the other end of
+    /// `cancelled`.
+    pub fn with_cancellation(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+}
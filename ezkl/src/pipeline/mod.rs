@@ -0,0 +1,117 @@
+//! Native replacement for the `run_ezkl.sh`/`run_ezkl_common.sh`/
+//! `run_ezkl_individual.sh` shell pipeline: each EZKL stage is a discrete
+//! [`Step`] driven directly via `std::process::Command`, sequenced by a
+//! [`Pipeline`]. This lets the crate run without any `.sh` files present,
+//! run individual stages independently for debugging, and skip stages whose
+//! artifacts already exist in `shared_circuit/`.
+
+pub mod context;
+pub mod steps;
+pub mod utils;
+
+use anyhow::{Result, anyhow};
+use colored::*;
+use std::sync::atomic::Ordering;
+
+pub use context::Context;
+pub use steps::Step;
+
+fn log_status(message: &str) {
+    println!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+fn log_success(message: &str) {
+    println!("[SUCCESS] {}", message.green());
+}
+
+fn log_info(message: &str) {
+    println!("[INFO] {}", message.blue());
+}
+
+/// An ordered sequence of pipeline steps, run against a shared [`Context`].
+pub struct Pipeline {
+    steps: Vec<Box<dyn Step>>,
+}
+
+impl Pipeline {
+    /// The standard proving pipeline: compile, setup, witness, prove,
+    /// verify, and (if `generate_contract`) the EVM verifier.
+    pub fn standard(generate_contract: bool) -> Self {
+        let mut steps: Vec<Box<dyn Step>> = vec![
+            Box::new(steps::CompileCircuit),
+            Box::new(steps::Setup),
+            Box::new(steps::GenWitness),
+            Box::new(steps::Prove),
+            Box::new(steps::Verify),
+        ];
+        if generate_contract {
+            steps.push(Box::new(steps::CreateEvmVerifier));
+        }
+        Self { steps }
+    }
+
+    /// The steps that write into the shared `shared_circuit/` directory.
+    /// Split out from [`Pipeline::standard`] so a caller running several
+    /// addresses concurrently (the task manager) can serialize only these
+    /// steps against each other instead of every address's whole pipeline.
+    /// `CreateEvmVerifier` belongs here rather than in
+    /// [`Pipeline::per_address_steps`]: it only depends on the shared
+    /// vk/SRS (now archived under `shared_circuit/contract/`), so running it
+    /// per address would redundantly regenerate an identical contract for
+    /// every proving request instead of once per circuit version.
+    pub fn shared_circuit_steps(generate_contract: bool) -> Self {
+        let mut steps: Vec<Box<dyn Step>> = vec![Box::new(steps::CompileCircuit), Box::new(steps::Setup)];
+        if generate_contract {
+            steps.push(Box::new(steps::CreateEvmVerifier));
+        }
+        Self { steps }
+    }
+
+    /// The remaining per-address steps, safe to run concurrently across
+    /// addresses once the shared circuit they depend on already exists.
+    pub fn per_address_steps() -> Self {
+        Self {
+            steps: vec![Box::new(steps::GenWitness), Box::new(steps::Prove), Box::new(steps::Verify)],
+        }
+    }
+
+    /// Runs every step in order, skipping ones whose artifacts already exist
+    /// so a rerun of a partially-completed pipeline doesn't redo finished
+    /// work. Checked again before each step (not just inside `run_command`'s
+    /// poll loop), so a cancellation raised while waiting on something
+    /// outside any single step — e.g. a caller's own lock around a shared
+    /// resource — is still caught before the next step starts rather than
+    /// only once that step's child is already spawned.
+    pub fn run(&self, ctx: &Context) -> Result<()> {
+        for step in &self.steps {
+            if ctx.cancelled.load(Ordering::SeqCst) {
+                return Err(anyhow!("Pipeline cancelled before step '{}'", step.name()));
+            }
+
+            if step.is_complete(ctx) {
+                log_info(&format!("Skipping '{}': artifact already present", step.name()));
+                continue;
+            }
+
+            log_status(&format!("Running step '{}'...", step.name()));
+            step.run(ctx)?;
+            log_success(&format!("Step '{}' completed", step.name()));
+        }
+        Ok(())
+    }
+
+    /// Runs a single named step, for debugging a pipeline stage in
+    /// isolation without rerunning everything before it.
+    pub fn run_step(&self, name: &str, ctx: &Context) -> Result<()> {
+        let step = self
+            .steps
+            .iter()
+            .find(|step| step.name() == name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown pipeline step: {}", name))?;
+
+        log_status(&format!("Running step '{}'...", step.name()));
+        step.run(ctx)?;
+        log_success(&format!("Step '{}' completed", step.name()));
+        Ok(())
+    }
+}
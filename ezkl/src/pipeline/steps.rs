@@ -0,0 +1,195 @@
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+
+use super::context::Context;
+use super::utils::{run_command, CommandOutput};
+
+/// A single discrete stage of the EZKL proving pipeline: compile, setup,
+/// witness generation, proving, verification, and (optionally) EVM verifier
+/// generation. Each step knows the output artifact it produces and can
+/// report whether that artifact already exists, so a `Pipeline` can skip
+/// stages that were completed by a previous run. A step does not check that
+/// its input files exist before running; a missing input surfaces as the
+/// failing `ezkl` command's error.
+pub trait Step {
+    fn name(&self) -> &'static str;
+
+    /// Whether this step's output artifact already exists, so reruns don't
+    /// redo work already present in `shared_circuit/`.
+    fn is_complete(&self, ctx: &Context) -> bool;
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput>;
+}
+
+pub struct CompileCircuit;
+
+impl Step for CompileCircuit {
+    fn name(&self) -> &'static str {
+        "compile-circuit"
+    }
+
+    fn is_complete(&self, ctx: &Context) -> bool {
+        ctx.compiled_circuit_path.exists()
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        fs::create_dir_all(&ctx.shared_circuit_dir)?;
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("compile-circuit")
+                .arg("-M")
+                .arg(&ctx.model_path)
+                .arg("--compiled-circuit")
+                .arg(&ctx.compiled_circuit_path)
+                .arg("-S")
+                .arg(&ctx.settings_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
+
+pub struct Setup;
+
+impl Step for Setup {
+    fn name(&self) -> &'static str {
+        "setup"
+    }
+
+    fn is_complete(&self, ctx: &Context) -> bool {
+        ctx.pk_path.exists() && ctx.vk_path.exists()
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("setup")
+                .arg("-M")
+                .arg(&ctx.compiled_circuit_path)
+                .arg("--pk-path")
+                .arg(&ctx.pk_path)
+                .arg("--vk-path")
+                .arg(&ctx.vk_path)
+                .arg("--srs-path")
+                .arg(&ctx.srs_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
+
+pub struct GenWitness;
+
+impl Step for GenWitness {
+    fn name(&self) -> &'static str {
+        "gen-witness"
+    }
+
+    fn is_complete(&self, ctx: &Context) -> bool {
+        ctx.witness_path.exists()
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("gen-witness")
+                .arg("-D")
+                .arg(&ctx.input_path)
+                .arg("-M")
+                .arg(&ctx.compiled_circuit_path)
+                .arg("-O")
+                .arg(&ctx.witness_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
+
+pub struct Prove;
+
+impl Step for Prove {
+    fn name(&self) -> &'static str {
+        "prove"
+    }
+
+    fn is_complete(&self, ctx: &Context) -> bool {
+        ctx.proof_path.exists()
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("prove")
+                .arg("--witness")
+                .arg(&ctx.witness_path)
+                .arg("--compiled-circuit")
+                .arg(&ctx.compiled_circuit_path)
+                .arg("--pk-path")
+                .arg(&ctx.pk_path)
+                .arg("--srs-path")
+                .arg(&ctx.srs_path)
+                .arg("--proof-path")
+                .arg(&ctx.proof_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
+
+pub struct Verify;
+
+impl Step for Verify {
+    fn name(&self) -> &'static str {
+        "verify"
+    }
+
+    // Verification has no artifact of its own to check for; it must run
+    // every time the proof it's checking changes.
+    fn is_complete(&self, _ctx: &Context) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("verify")
+                .arg("--proof-path")
+                .arg(&ctx.proof_path)
+                .arg("--vk-path")
+                .arg(&ctx.vk_path)
+                .arg("--srs-path")
+                .arg(&ctx.srs_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
+
+pub struct CreateEvmVerifier;
+
+impl Step for CreateEvmVerifier {
+    fn name(&self) -> &'static str {
+        "create-evm-verifier"
+    }
+
+    fn is_complete(&self, ctx: &Context) -> bool {
+        ctx.contract_dir.join("verifier.sol").exists()
+    }
+
+    fn run(&self, ctx: &Context) -> Result<CommandOutput> {
+        fs::create_dir_all(&ctx.contract_dir)?;
+        run_command(
+            Command::new(&ctx.ezkl_binary)
+                .arg("create-evm-verifier")
+                .arg("--vk-path")
+                .arg(&ctx.vk_path)
+                .arg("--sol-code-path")
+                .arg(ctx.contract_dir.join("verifier.sol"))
+                .arg("--srs-path")
+                .arg(&ctx.srs_path),
+            self.name(),
+            &ctx.cancelled,
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result, anyhow};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Captured status/stdout/stderr from a single command invocation, uniform
+/// across every pipeline step so callers don't each re-implement the same
+/// `output()` + status check.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// How often to poll a running child for exit and for cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Runs `cmd` to completion, returning its captured stdout/stderr on success
+/// and a descriptive error (including both streams) on a non-zero exit
+/// status.
+///
+/// Unlike `Command::output()`, this keeps a live [`std::process::Child`]
+/// handle for the whole run by polling `try_wait()` instead of blocking on
+/// it, so `cancelled` is checked while the step is still in flight and a
+/// cancellation actually kills the `ezkl` child instead of only taking
+/// effect after it finishes on its own.
+pub fn run_command(cmd: &mut Command, step_name: &str, cancelled: &Arc<AtomicBool>) -> Result<CommandOutput> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to spawn command for step '{}'", step_name))?;
+
+    let mut stdout_pipe = child.stdout.take().expect("stdout was requested as piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr was requested as piped");
+    let stdout_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout_pipe.read_to_end(&mut buf);
+        buf
+    });
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut buf);
+        buf
+    });
+
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("Failed to poll command for step '{}'", step_name))?
+        {
+            break status;
+        }
+
+        if cancelled.load(Ordering::SeqCst) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("Step '{}' was cancelled", step_name));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let stdout = String::from_utf8_lossy(&stdout_reader.join().unwrap_or_default()).into_owned();
+    let stderr = String::from_utf8_lossy(&stderr_reader.join().unwrap_or_default()).into_owned();
+
+    if !status.success() {
+        return Err(anyhow!(
+            "Step '{}' failed with status {}\nstdout:\n{}\nstderr:\n{}",
+            step_name,
+            status,
+            stdout,
+            stderr
+        ));
+    }
+
+    Ok(CommandOutput { stdout, stderr })
+}
+
+/// Resolves the `ezkl` binary: `configured` is used as-is if it names an
+/// existing path (an absolute override from [`crate::config::EzklConfig`]),
+/// otherwise it's looked up on `PATH` (covering the common case where it's
+/// left at the default `"ezkl"`).
+pub fn resolve_ezkl_binary(configured: &Path) -> Result<PathBuf> {
+    if configured.is_absolute() && configured.exists() {
+        return Ok(configured.to_path_buf());
+    }
+
+    which::which(configured).map_err(|_| {
+        anyhow!(
+            "EZKL binary '{}' not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl",
+            configured.display()
+        )
+    })
+}
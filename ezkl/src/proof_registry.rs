@@ -1,23 +1,235 @@
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, anyhow};
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::Path;
 
-/// Creates a proof registry entry for the given address
-/// Returns a boolean indicating success
+use crate::utils::address_to_filename;
+
+/// The bn256 (BN254) scalar field order, i.e. the modulus every public
+/// instance limb EZKL emits is already reduced under.
+const BN256_FR_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// A fully-decoded proof registry entry. `credit_score` stays the primary
+/// output for backwards compatibility with existing consumers, while
+/// `public_inputs` carries every decoded instance so multi-output models
+/// aren't silently truncated to one value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofMetadata {
+    pub proof_hash: String,
+    pub credit_score: u32,
+    /// Every decoded public instance, in the order EZKL emitted them, as
+    /// base-10 strings (a `BigUint` doesn't serialize to JSON numbers safely
+    /// once it exceeds 2^53).
+    pub public_inputs: Vec<String>,
+    pub timestamp: u64,
+    pub model_version: String,
+    pub address: String,
+}
+
+/// Parses every entry in a proof's `instances` array into a full 256-bit
+/// scalar rather than grabbing only `instances[0][0]`. Each limb is 32
+/// little-endian bytes (EZKL's own encoding), and the raw value is reduced
+/// modulo the bn256 scalar field order since that's the field every
+/// committed instance lives in.
+pub fn decode_instances(proof_json: &serde_json::Value) -> Result<Vec<BigUint>> {
+    let modulus = BigUint::from_str_radix(BN256_FR_MODULUS, 10)
+        .expect("BN256_FR_MODULUS is a valid base-10 literal");
+
+    let instances = proof_json
+        .get("instances")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("proof.json is missing an 'instances' array"))?;
+
+    let mut decoded = Vec::new();
+    for column in instances {
+        let column = column
+            .as_array()
+            .ok_or_else(|| anyhow!("proof.json instances entry is not an array"))?;
+        for limb in column {
+            let hex_str = limb
+                .as_str()
+                .ok_or_else(|| anyhow!("instance limb is not a hex string"))?
+                .trim_start_matches("0x");
+
+            // Hex strings of odd length can't be byte-paired; pad a leading
+            // zero rather than dropping the final nibble.
+            let padded;
+            let hex_str = if hex_str.len() % 2 == 0 {
+                hex_str
+            } else {
+                padded = format!("0{}", hex_str);
+                &padded
+            };
+
+            let bytes = hex::decode(hex_str)
+                .with_context(|| format!("Failed to decode instance limb '{}'", hex_str))?;
+            // EZKL emits each 32-byte limb little-endian already.
+            let value = BigUint::from_bytes_le(&bytes);
+            decoded.push(value % &modulus);
+        }
+    }
+
+    Ok(decoded)
+}
+
+/// Creates a proof registry entry for the given address, decoding the full
+/// public-input vector instead of truncating it to a single `u64`.
 pub fn create_proof_registry(address: &str, proof_dir: &str) -> Result<bool, anyhow::Error> {
-    // Read the proof from the proof directory
     let proof_path = format!("{}/proof.json", proof_dir);
     let proof_data = fs::read_to_string(Path::new(&proof_path))
         .context(format!("Failed to read proof data from {}", proof_path))?;
-    
-    // Store the proof in the registry directory
+
+    let mut hasher = Sha256::new();
+    hasher.update(proof_data.as_bytes());
+    let proof_hash = hex::encode(hasher.finalize());
+
+    let proof_json: serde_json::Value = serde_json::from_str(&proof_data)
+        .context(format!("Failed to parse proof data from {}", proof_path))?;
+
+    let public_inputs = decode_instances(&proof_json)?;
+    let credit_score = match public_inputs.first() {
+        Some(v) => u32::try_from(v.clone()).unwrap_or_else(|_| {
+            // The full value is still preserved in `public_inputs` below, so
+            // this isn't a data loss bug, but a model that emits an
+            // out-of-u32-range primary output is unexpected enough to flag
+            // loudly rather than let `credit_score` quietly read back as 0.
+            eprintln!(
+                "[ERROR] proof for {} has a primary output of {} which doesn't fit in u32; recording credit_score as 0, see public_inputs[0] for the true value",
+                address, v
+            );
+            0
+        }),
+        None => 0,
+    };
+
+    let registry_entry = ProofMetadata {
+        proof_hash: proof_hash.clone(),
+        credit_score,
+        public_inputs: public_inputs.iter().map(|v| v.to_string()).collect(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs(),
+        model_version: "1.0.0".to_string(),
+        address: address.to_string(),
+    };
+
+    write_scaling_analysis(address, proof_dir, &public_inputs)?;
+
     let registry_dir = "proof_registry";
     fs::create_dir_all(registry_dir)?;
-    
-    // Save the proof with the address as the filename
-    let registry_path = format!("{}/{}.json", registry_dir, address);
-    fs::write(&registry_path, &proof_data)
+    let registry_path = format!("{}/{}.json", registry_dir, address_to_filename(address));
+    fs::write(&registry_path, serde_json::to_string_pretty(&registry_entry)?)
         .context(format!("Failed to write proof to registry at {}", registry_path))?;
-    
+
     Ok(true)
 }
+
+/// Reports the scaling factor between each decoded public output and the
+/// model's scaled score, for every output rather than only the first. The
+/// scaled scores come from the model's own `metadata.json` (a
+/// `scaled_scores` array, or else a single `scaled_score`); any output without a corresponding metadata entry is reported with a
+/// `null` scaling factor instead of silently defaulting to zero.
+fn write_scaling_analysis(address: &str, proof_dir: &str, public_inputs: &[BigUint]) -> Result<()> {
+    let metadata_path = format!("{}/metadata.json", proof_dir);
+    let metadata: serde_json::Value = match fs::read_to_string(&metadata_path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or(serde_json::Value::Null),
+        Err(_) => serde_json::Value::Null,
+    };
+
+    let scaled_scores: Vec<f64> = metadata
+        .get("scaled_scores")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
+        .unwrap_or_else(|| {
+            metadata
+                .get("scaled_score")
+                .and_then(|v| v.as_f64())
+                .map(|v| vec![v])
+                .unwrap_or_default()
+        });
+
+    let per_output: Vec<serde_json::Value> = public_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let value_f64 = value.to_string().parse::<f64>().unwrap_or(f64::NAN);
+            let scaling_factor = scaled_scores
+                .get(i)
+                .filter(|&&scaled| scaled != 0.0)
+                .map(|&scaled| value_f64 / scaled);
+
+            serde_json::json!({
+                "output_index": i,
+                "public_input": value.to_string(),
+                "scaled_score": scaled_scores.get(i),
+                "scaling_factor": scaling_factor,
+            })
+        })
+        .collect();
+
+    let scaling_debug = serde_json::json!({
+        "address": address,
+        "outputs": per_output,
+    });
+
+    fs::write(
+        format!("{}/scaling_analysis.json", proof_dir),
+        serde_json::to_string_pretty(&scaling_debug)?,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof_json_with_limbs(limbs: &[&str]) -> serde_json::Value {
+        serde_json::json!({ "instances": [limbs] })
+    }
+
+    #[test]
+    fn decode_instances_reads_little_endian_hex_limbs() {
+        // 0x2a little-endian in a 32-byte limb decodes back to 42.
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0x2a;
+        let hex_limb = format!("0x{}", hex::encode(bytes));
+        let proof_json = proof_json_with_limbs(&[&hex_limb]);
+
+        let decoded = decode_instances(&proof_json).unwrap();
+
+        assert_eq!(decoded, vec![BigUint::from(42u32)]);
+    }
+
+    #[test]
+    fn decode_instances_pads_odd_length_hex() {
+        let proof_json = proof_json_with_limbs(&["0xa"]);
+
+        let decoded = decode_instances(&proof_json).unwrap();
+
+        assert_eq!(decoded, vec![BigUint::from(0x0au32)]);
+    }
+
+    #[test]
+    fn decode_instances_reduces_modulo_bn256_fr() {
+        let modulus = BigUint::from_str_radix(BN256_FR_MODULUS, 10).unwrap();
+        let bytes = (&modulus + BigUint::from(5u32)).to_bytes_le();
+        let hex_limb = format!("0x{}", hex::encode(bytes));
+        let proof_json = proof_json_with_limbs(&[&hex_limb]);
+
+        let decoded = decode_instances(&proof_json).unwrap();
+
+        assert_eq!(decoded, vec![BigUint::from(5u32)]);
+    }
+
+    #[test]
+    fn decode_instances_rejects_missing_instances_array() {
+        let proof_json = serde_json::json!({});
+
+        assert!(decode_instances(&proof_json).is_err());
+    }
+}
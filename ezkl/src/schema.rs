@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Context, Result};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INPUT_SCHEMA_FILE: &str = "input.schema.json";
+const SETTINGS_SCHEMA_FILE: &str = "settings.schema.json";
+
+/// Mirrors the JSON document `create_address_input` writes, so the schema
+/// generated from this struct can never drift from what's actually handed
+/// to EZKL the way a hand-maintained schema file could.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EzklInput {
+    pub input_data: Vec<Vec<f32>>,
+    pub input_shapes: Vec<Vec<usize>>,
+    pub output_data: Vec<Vec<f32>>,
+}
+
+/// The subset of `settings.json` this crate actually reads downstream
+/// (`read_logrows`, `read_constraint_system_meta`, `circuit_registry`).
+/// Deliberately partial: EZKL owns the full settings format, so unknown
+/// fields are left alone rather than rejected.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EzklSettingsShape {
+    pub run_args: RunArgsShape,
+    pub model_input_scales: Vec<f64>,
+    pub model_output_scales: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunArgsShape {
+    pub logrows: u32,
+}
+
+pub fn input_schema_path(proof_gen_dir: &Path) -> PathBuf {
+    proof_gen_dir.join(INPUT_SCHEMA_FILE)
+}
+
+pub fn settings_schema_path(proof_gen_dir: &Path) -> PathBuf {
+    proof_gen_dir.join(SETTINGS_SCHEMA_FILE)
+}
+
+/// Writes the derived input-document schema to disk so frontends and CI can
+/// validate against the same contract this crate validates against.
+pub fn write_input_schema(proof_gen_dir: &Path) -> Result<()> {
+    let schema = schema_for!(EzklInput);
+    let path = input_schema_path(proof_gen_dir);
+    fs::write(&path, serde_json::to_string_pretty(&schema)?)
+        .with_context(|| format!("Failed to write input schema to {}", path.display()))
+}
+
+/// Writes the derived settings schema to disk alongside the input schema.
+pub fn write_settings_schema(proof_gen_dir: &Path) -> Result<()> {
+    let schema = schema_for!(EzklSettingsShape);
+    let path = settings_schema_path(proof_gen_dir);
+    fs::write(&path, serde_json::to_string_pretty(&schema)?)
+        .with_context(|| format!("Failed to write settings schema to {}", path.display()))
+}
+
+/// Checks the cross-field invariants a JSON Schema can't express on its
+/// own: `input_shapes`' declared shape actually matches `input_data`'s
+/// length, and every feature/output value is finite (catching NaN/Inf
+/// before they reach EZKL, which would otherwise fail deep in the
+/// pipeline with an opaque error).
+pub fn validate_input(input: &EzklInput) -> Result<()> {
+    if input.input_data.len() != input.input_shapes.len() {
+        return Err(anyhow!(
+            "input_data has {} row(s) but input_shapes declares {}",
+            input.input_data.len(),
+            input.input_shapes.len()
+        ));
+    }
+
+    for (row_idx, (row, shape)) in input.input_data.iter().zip(&input.input_shapes).enumerate() {
+        let expected_len: usize = shape.iter().product();
+        if row.len() != expected_len {
+            return Err(anyhow!(
+                "input_data[{}] has {} value(s) but input_shapes[{}] = {:?} expects {}",
+                row_idx,
+                row.len(),
+                row_idx,
+                shape,
+                expected_len
+            ));
+        }
+        for (feature_idx, value) in row.iter().enumerate() {
+            if !value.is_finite() {
+                return Err(anyhow!(
+                    "input_data[{}][{}] is {} (NaN/infinite values can't be committed to a circuit)",
+                    row_idx,
+                    feature_idx,
+                    value
+                ));
+            }
+        }
+    }
+
+    if input.output_data.is_empty() {
+        return Err(anyhow!("output_data must contain at least one placeholder row"));
+    }
+    for (row_idx, row) in input.output_data.iter().enumerate() {
+        for (col_idx, value) in row.iter().enumerate() {
+            if !value.is_finite() {
+                return Err(anyhow!(
+                    "output_data[{}][{}] is {} (must be finite)",
+                    row_idx,
+                    col_idx,
+                    value
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses `path` as an [`EzklInput`] — which on its own catches malformed
+/// JSON and wrong field types — and then checks [`validate_input`]'s
+/// cross-field invariants.
+pub fn validate_input_file(path: &Path) -> Result<EzklInput> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read input document at {}", path.display()))?;
+    let input: EzklInput = serde_json::from_str(&data)
+        .with_context(|| format!("{} does not match the EZKL input schema", path.display()))?;
+    validate_input(&input)?;
+    Ok(input)
+}
+
+/// Parses `path` against [`EzklSettingsShape`], failing with a precise
+/// location if `gen-settings`/`calibrate-settings` produced something this
+/// crate's downstream readers (`read_logrows`, `read_constraint_system_meta`)
+/// wouldn't be able to make sense of.
+pub fn validate_settings_file(path: &Path) -> Result<EzklSettingsShape> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read settings document at {}", path.display()))?;
+    let settings: EzklSettingsShape = serde_json::from_str(&data)
+        .with_context(|| format!("{} does not match the expected settings schema", path.display()))?;
+
+    if settings.model_input_scales.is_empty() {
+        return Err(anyhow!("{}: model_input_scales must not be empty", path.display()));
+    }
+    if settings.model_output_scales.is_empty() {
+        return Err(anyhow!("{}: model_output_scales must not be empty", path.display()));
+    }
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_settings_file(contents: &str) -> PathBuf {
+        let n = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let mut path = std::env::temp_dir();
+        path.push(format!("ezkl_schema_test_{}_{}.json", std::process::id(), n));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn valid_input() -> EzklInput {
+        EzklInput {
+            input_data: vec![vec![1.0, 2.0]],
+            input_shapes: vec![vec![2]],
+            output_data: vec![vec![0.0]],
+        }
+    }
+
+    #[test]
+    fn validate_input_rejects_row_count_mismatch() {
+        let mut input = valid_input();
+        input.input_shapes.push(vec![1]);
+
+        let err = validate_input(&input).unwrap_err();
+        assert!(err.to_string().contains("input_shapes declares"));
+    }
+
+    #[test]
+    fn validate_input_rejects_shape_length_mismatch() {
+        let mut input = valid_input();
+        input.input_shapes = vec![vec![3]];
+
+        let err = validate_input(&input).unwrap_err();
+        assert!(err.to_string().contains("expects 3"));
+    }
+
+    #[test]
+    fn validate_input_rejects_nan_in_input_data() {
+        let mut input = valid_input();
+        input.input_data[0][1] = f32::NAN;
+
+        let err = validate_input(&input).unwrap_err();
+        assert!(err.to_string().contains("NaN/infinite"));
+    }
+
+    #[test]
+    fn validate_input_rejects_non_finite_output_data() {
+        let mut input = valid_input();
+        input.output_data[0][0] = f32::INFINITY;
+
+        let err = validate_input(&input).unwrap_err();
+        assert!(err.to_string().contains("must be finite"));
+    }
+
+    #[test]
+    fn validate_input_accepts_a_well_formed_document() {
+        assert!(validate_input(&valid_input()).is_ok());
+    }
+
+    #[test]
+    fn validate_settings_file_rejects_empty_input_scales() {
+        let path = temp_settings_file(
+            r#"{"run_args": {"logrows": 10}, "model_input_scales": [], "model_output_scales": [7.0]}"#,
+        );
+
+        let err = validate_settings_file(&path).unwrap_err();
+
+        fs::remove_file(&path).ok();
+        assert!(err.to_string().contains("model_input_scales must not be empty"));
+    }
+
+    #[test]
+    fn validate_settings_file_rejects_empty_output_scales() {
+        let path = temp_settings_file(
+            r#"{"run_args": {"logrows": 10}, "model_input_scales": [7.0], "model_output_scales": []}"#,
+        );
+
+        let err = validate_settings_file(&path).unwrap_err();
+
+        fs::remove_file(&path).ok();
+        assert!(err.to_string().contains("model_output_scales must not be empty"));
+    }
+
+    #[test]
+    fn validate_settings_file_accepts_a_well_formed_document() {
+        let path = temp_settings_file(
+            r#"{"run_args": {"logrows": 10}, "model_input_scales": [7.0], "model_output_scales": [7.0]}"#,
+        );
+
+        let result = validate_settings_file(&path);
+
+        fs::remove_file(&path).ok();
+        assert!(result.is_ok());
+    }
+}
@@ -1,19 +1,28 @@
 use anyhow::{Result, Context, anyhow};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::fs;
 use colored::*;
 
+use crate::artifact_cache::{digest_bytes, Manifest};
+use crate::circuit_registry;
+use crate::config::EzklConfig;
+use crate::pipeline::utils::resolve_ezkl_binary;
+use crate::pipeline::{Context as PipelineContext, Pipeline};
+use crate::schema;
+use crate::srs::ensure_srs;
+
 pub const MODEL_NAME: &str = "credit_model.onnx";
+/// Legacy relative proof-generation directory, kept as the default only for
+/// code paths that haven't been threaded through an [`EzklConfig`] yet.
+/// Prefer `config.proof_output_dir`.
 pub const PROOF_GEN_DIR: &str = "proof_generation";
 pub const SRS_FILE: &str = "kzg.srs";
 
-// Define shell script paths
-pub const SHELL_SCRIPTS: &[&str] = &[
-    "./run_ezkl.sh",
-    "./run_ezkl_common.sh",
-    "./run_ezkl_individual.sh"
-];
+/// Bumped whenever `script/create_model.py`'s output for the same
+/// (features, address) pair would change, so the model cache invalidates
+/// without needing to diff the script itself.
+const PYTHON_GENERATOR_VERSION: &str = "create_model.py-v1";
 
 /// Log a status message with timestamp
 fn log_status(message: &str) {
@@ -41,138 +50,198 @@ fn log_info(message: &str) {
     println!("[INFO] {}", message.blue());
 }
 
-/// Creates the shared model and downloads SRS file if needed
-pub fn initialize_shared_resources(features: &[f32], address: &str) -> Result<(), anyhow::Error> {
+/// Creates the shared model and downloads SRS file if needed. Every path
+/// involved (proof output directory, EZKL binary) comes from `config`
+/// instead of hardcoded constants, so the crate can run in multiple
+/// workspaces and CI environments cleanly. The SRS stage's download through
+/// [`ensure_srs`] does not use `config`: it runs the `ezkl` found on `PATH`
+/// and caches under the OS data directory before the result is copied into
+/// the proof output directory.
+///
+/// Each stage (model, settings, SRS) is gated by a keyed-BLAKE3 digest of
+/// its real inputs recorded in `manifest.json`, rather than by "does the
+/// output file exist": a rerun with the same `features`/`address` reuses
+/// the existing artifacts, a rerun with different ones regenerates only the
+/// stages whose inputs actually changed, and an artifact whose on-disk
+/// digest no longer matches its manifest entry (truncated download,
+/// tampering) is regenerated instead of silently trusted.
+pub fn initialize_shared_resources(features: &[f32], address: &str, config: &EzklConfig) -> Result<(), anyhow::Error> {
     log_status("Initializing shared resources...");
-    
-    // Ensure proof_generation directory exists
-    match fs::create_dir_all(PROOF_GEN_DIR) {
-        Ok(_) => log_info(&format!("Directory '{}' is ready", PROOF_GEN_DIR)),
+
+    let proof_gen_dir = &config.proof_output_dir;
+
+    match fs::create_dir_all(proof_gen_dir) {
+        Ok(_) => log_info(&format!("Directory '{}' is ready", proof_gen_dir.display())),
         Err(e) => {
-            log_error(&format!("Failed to create directory '{}': {}", PROOF_GEN_DIR, e));
-            return Err(anyhow::anyhow!("Failed to create directory '{}': {}", PROOF_GEN_DIR, e));
+            log_error(&format!("Failed to create directory '{}': {}", proof_gen_dir.display(), e));
+            return Err(anyhow::anyhow!("Failed to create directory '{}': {}", proof_gen_dir.display(), e));
         }
     };
 
-    // Generate the shared model
-    let model_path = Path::new(PROOF_GEN_DIR).join(MODEL_NAME);
-    if !model_path.exists() {
+    schema::write_input_schema(proof_gen_dir)?;
+    schema::write_settings_schema(proof_gen_dir)?;
+
+    let mut manifest = Manifest::load(proof_gen_dir)?;
+    let ezkl_bin = resolve_ezkl_binary(&config.ezkl_binary)?;
+    log_info(&format!("Using EZKL binary at: {}", ezkl_bin.display()));
+
+    // Stage: model
+    let model_path = proof_gen_dir.join(MODEL_NAME);
+    let model_inputs_digest = digest_bytes(
+        format!("{:?}|{}|{}", features, address, PYTHON_GENERATOR_VERSION).as_bytes(),
+    )?;
+
+    if model_path.exists() && manifest.is_fresh("model", &model_inputs_digest, &model_path) {
+        log_info(&format!("Shared model already up to date at {}", model_path.display()));
+    } else {
         log_status("Generating shared model...");
-        match create_model(features, address, PROOF_GEN_DIR, true) {
+        match create_model(features, address, proof_gen_dir, true) {
             Ok(_) => log_success("Shared model created successfully"),
             Err(e) => {
                 log_error(&format!("Failed to create shared model: {}", e));
                 return Err(e);
             }
         }
-    } else {
-        log_info(&format!("Shared model already exists at {}", model_path.display()));
+        manifest.record("model", &model_inputs_digest, &model_path)?;
     }
 
     // Get absolute paths
     let model_path_abs = fs::canonicalize(&model_path)?;
     let model_path_str = model_path_abs.to_string_lossy().into_owned();
-    
-    // Generate settings file
-    log_status("Generating settings file...");
-    let settings_path = Path::new(PROOF_GEN_DIR).join("settings.json");
-    
-    let ezkl_bin = which::which("ezkl").map_err(|_| {
-        log_error("EZKL command not found in PATH. Make sure EZKL is installed correctly.");
-        anyhow::anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl")
-    })?;
-    
-    log_info(&format!("Using EZKL binary at: {}", ezkl_bin.display()));
-    
-    let output = Command::new(ezkl_bin.clone())
-        .arg("gen-settings")
-        .arg("-M")
-        .arg(&model_path_str)
-        .arg("-O")
-        .arg(&settings_path)
-        .output()
-        .context("Failed to execute EZKL gen-settings command")?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log_error(&format!("Failed to generate settings: {}", stderr));
-        return Err(anyhow::anyhow!("Failed to generate settings: {}", stderr));
-    }
-    
-    log_success("Settings generated successfully");
-
-    // Create a dummy input.json for calibration
-    create_address_input(features, address, PROOF_GEN_DIR)?;
-
-    // Calibrate settings
-    log_status("Calibrating settings...");
-    
-    // First check if input.json exists
-    let input_path = format!("{}/input.json", PROOF_GEN_DIR);
-    if !Path::new(&input_path).exists() {
-        log_error(&format!("Input file not found at: {}", input_path));
-        return Err(anyhow::anyhow!("Input file not found at: {}. Make sure address input was created correctly.", input_path));
-    }
-    
-    let output = Command::new(&ezkl_bin)
-        .arg("calibrate-settings")
-        .arg("-M")
-        .arg(&model_path_str)
-        .arg("-D")
-        .arg(&input_path)
-        .arg("-O")
-        .arg(&settings_path)
-        .output()
-        .context("Failed to execute EZKL calibrate-settings command")?;
+    // Stage: settings
+    let settings_path = proof_gen_dir.join("settings.json");
+    let ezkl_version = read_ezkl_version(&ezkl_bin)?;
+    let model_digest = manifest
+        .output_digest("model")
+        .ok_or_else(|| anyhow!("Missing model digest in manifest after model stage"))?
+        .to_string();
+    let settings_inputs_digest = digest_bytes(format!("{}|{}", model_digest, ezkl_version).as_bytes())?;
+
+    if settings_path.exists() && manifest.is_fresh("settings", &settings_inputs_digest, &settings_path) {
+        log_info(&format!("Settings already up to date at {}", settings_path.display()));
+    } else {
+        log_status("Generating settings file...");
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log_error(&format!("Failed to calibrate settings: {}", stderr));
-        return Err(anyhow::anyhow!("Failed to calibrate settings: {}", stderr));
-    }
-    
-    // Log the calibration output since it contains useful information
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    println!("{}", stdout);
-    
-    log_success("Settings calibrated successfully");
-
-    // Download SRS file if needed
-    let srs_path = Path::new(PROOF_GEN_DIR).join(SRS_FILE);
-    let settings_path = Path::new(PROOF_GEN_DIR).join("settings.json");
-    
-    // Check if settings.json exists before continuing
-    if !settings_path.exists() {
-        log_error(&format!("Settings file not found at: {}", settings_path.display()));
-        return Err(anyhow::anyhow!("Settings file not found at: {}. Make sure settings generation completed successfully.", settings_path.display()));
-    }
-    
-    if !srs_path.exists() {
-        log_status("Downloading SRS file...");
-        log_info("This may take a while for large parameters...");
-        
         let output = Command::new(&ezkl_bin)
-            .arg("get-srs")
-            .arg("--settings-path")
+            .arg("gen-settings")
+            .arg("-M")
+            .arg(&model_path_str)
+            .arg("-O")
             .arg(&settings_path)
-            .arg("--srs-path")
-            .arg(&srs_path)
             .output()
-            .context("Failed to execute EZKL get-srs command")?;
+            .context("Failed to execute EZKL gen-settings command")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            log_error(&format!("Failed to download SRS file: {}", stderr));
-            return Err(anyhow::anyhow!("Failed to download SRS file: {}. Check your network connection.", stderr));
+            log_error(&format!("Failed to generate settings: {}", stderr));
+            return Err(anyhow::anyhow!("Failed to generate settings: {}", stderr));
+        }
+
+        log_success("Settings generated successfully");
+
+        // Create a dummy input.json for calibration
+        create_address_input(features, address, proof_gen_dir.to_string_lossy().as_ref())?;
+
+        // Calibrate settings
+        log_status("Calibrating settings...");
+
+        let input_path = proof_gen_dir.join("input.json");
+        if !input_path.exists() {
+            log_error(&format!("Input file not found at: {}", input_path.display()));
+            return Err(anyhow::anyhow!("Input file not found at: {}. Make sure address input was created correctly.", input_path.display()));
+        }
+
+        let output = Command::new(&ezkl_bin)
+            .arg("calibrate-settings")
+            .arg("-M")
+            .arg(&model_path_str)
+            .arg("-D")
+            .arg(&input_path)
+            .arg("-O")
+            .arg(&settings_path)
+            .output()
+            .context("Failed to execute EZKL calibrate-settings command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log_error(&format!("Failed to calibrate settings: {}", stderr));
+            return Err(anyhow::anyhow!("Failed to calibrate settings: {}", stderr));
+        }
+
+        // Log the calibration output since it contains useful information
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        println!("{}", stdout);
+
+        log_success("Settings calibrated successfully");
+
+        if !settings_path.exists() {
+            log_error(&format!("Settings file not found at: {}", settings_path.display()));
+            return Err(anyhow::anyhow!("Settings file not found at: {}. Make sure settings generation completed successfully.", settings_path.display()));
         }
-        log_success("SRS file downloaded successfully");
+
+        manifest.record("settings", &settings_inputs_digest, &settings_path)?;
+    }
+
+    schema::validate_settings_file(&settings_path)
+        .context("EZKL-generated settings.json failed schema validation")?;
+
+    // Stage: SRS
+    let srs_dest = proof_gen_dir.join(SRS_FILE);
+    let settings_digest = manifest
+        .output_digest("settings")
+        .ok_or_else(|| anyhow!("Missing settings digest in manifest after settings stage"))?
+        .to_string();
+    let srs_inputs_digest = digest_bytes(settings_digest.as_bytes())?;
+
+    if srs_dest.exists() && manifest.is_fresh("srs", &srs_inputs_digest, &srs_dest) {
+        log_info(&format!("SRS already up to date at {}", srs_dest.display()));
     } else {
-        log_info(&format!("SRS file already exists at {}", srs_path.display()));
+        // Ensure a verified SRS exists for this circuit's logrows, downloading
+        // and integrity-checking it (or deriving it from the cached maximal
+        // SRS) instead of inlining the bash `if [ ! -f kzg.srs ]` download.
+        let logrows = read_logrows(&settings_path).unwrap_or(20);
+        let srs_path = ensure_srs(logrows)?;
+        fs::copy(&srs_path, &srs_dest)
+            .context("Failed to copy verified SRS into the proof output directory")?;
+        manifest.record("srs", &srs_inputs_digest, &srs_dest)?;
+        log_success(&format!("SRS ready at {}", srs_path.display()));
     }
 
+    manifest.save(proof_gen_dir)?;
+
     Ok(())
 }
 
+/// Shells out to `ezkl --version` so the settings cache invalidates when the
+/// installed EZKL binary changes, not just when `features`/`address` do.
+fn read_ezkl_version(ezkl_bin: &Path) -> Result<String, anyhow::Error> {
+    let output = Command::new(ezkl_bin)
+        .arg("--version")
+        .output()
+        .context("Failed to execute EZKL --version command")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to read EZKL version: {}", stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reads `run_args.logrows` from a settings file, used to pick the smallest
+/// SRS degree that covers this circuit.
+fn read_logrows(settings_path: &Path) -> Result<u32, anyhow::Error> {
+    let data = fs::read_to_string(settings_path)?;
+    let settings: serde_json::Value = serde_json::from_str(&data)?;
+    settings
+        .get("run_args")
+        .and_then(|args| args.get("logrows"))
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .ok_or_else(|| anyhow!("settings.json is missing run_args.logrows"))
+}
+
 /// Creates address-specific input.json file
 pub fn create_address_input(features: &[f32], address: &str, output_dir: &str) -> Result<(), anyhow::Error> {
     log_status(&format!("Creating input for address: {}", address));
@@ -187,17 +256,18 @@ pub fn create_address_input(features: &[f32], address: &str, output_dir: &str) -
         }
     };
 
-    // Create the input data in EZKL format with nested arrays
-    let ezkl_input = serde_json::json!({
-        "input_data": [
-            features.to_vec()  // Wrap features in an additional array
-        ],
-        "input_shapes": [[features.len()]],
-        "output_data": [
-            [0.0]  // Placeholder output
-        ]
-    });
-    
+    // Build the input document against the strongly-typed schema in
+    // `schema::EzklInput` instead of hand-assembling `serde_json::json!`,
+    // so a features/shape mismatch or a NaN is caught here with a precise
+    // location rather than surfacing as an opaque EZKL failure later.
+    let ezkl_input = schema::EzklInput {
+        input_data: vec![features.to_vec()],
+        input_shapes: vec![vec![features.len()]],
+        output_data: vec![vec![0.0]], // Placeholder output
+    };
+    schema::validate_input(&ezkl_input)
+        .with_context(|| format!("Generated input for address {} failed schema validation", address))?;
+
     // Write the formatted input
     let input_path = Path::new(output_dir).join("input.json");
     fs::write(&input_path, serde_json::to_string_pretty(&ezkl_input)?)
@@ -208,10 +278,10 @@ pub fn create_address_input(features: &[f32], address: &str, output_dir: &str) -
 }
 
 // Helper function used by initialize_shared_resources
-fn create_model(features: &[f32], address: &str, output_dir: &str, force_generate_model: bool) -> Result<(), anyhow::Error> {
+fn create_model(features: &[f32], address: &str, output_dir: &Path, force_generate_model: bool) -> Result<(), anyhow::Error> {
     // Convert features to JSON string
     let features_json = serde_json::to_string(features)?;
-    
+
     // Call Python script to generate model
     let status = Command::new("python3")
         .arg("./script/create_model.py")
@@ -229,99 +299,40 @@ fn create_model(features: &[f32], address: &str, output_dir: &str, force_generat
     Ok(())
 }
 
-pub fn create_ezkl_script(script_path: &Path, working_dir: &str, generate_contract: bool) -> Result<(), anyhow::Error> {
-    // Ensure the shared model exists
-    let model_path = Path::new(PROOF_GEN_DIR).join(MODEL_NAME);
+/// Drives the full EZKL proving pipeline for `address_dir` natively via
+/// [`Pipeline`], replacing the old `run_ezkl.sh`/`run_ezkl_common.sh`/
+/// `run_ezkl_individual.sh` launcher script. The circuit (`model.compiled`,
+/// `pk.key`, `vk.key`) is shared across addresses in `shared_circuit/` and
+/// only (re)compiled/setup once; witness and proof artifacts are per-address.
+pub fn run_ezkl_pipeline(address_dir: &Path, generate_contract: bool, config: &EzklConfig) -> Result<(), anyhow::Error> {
+    let model_path = config.proof_output_dir.join(MODEL_NAME);
     if !model_path.exists() {
         return Err(anyhow::anyhow!("Shared model not found at: {}", model_path.display()));
     }
 
-    // Get absolute paths
-    let working_dir_abs = fs::canonicalize(working_dir)?;
-    let model_path_abs = fs::canonicalize(&model_path)?;
-    let srs_path_abs = fs::canonicalize(Path::new(PROOF_GEN_DIR).join(SRS_FILE))?;
+    let srs_path: PathBuf = config.proof_output_dir.join(SRS_FILE);
+    let ctx = PipelineContext::new(config, address_dir, &model_path, &srs_path, generate_contract)?;
+
+    // Validate whatever's at `input.json` against the input schema before
+    // handing it to `gen-witness`, whether it was produced by
+    // `create_address_input` or supplied directly by a caller.
+    schema::validate_input_file(&ctx.input_path)
+        .with_context(|| format!("{} failed schema validation", ctx.input_path.display()))?;
+
+    Pipeline::standard(generate_contract).run(&ctx)?;
+
+    // Stamp whatever circuit `setup` just produced (or reused) as the
+    // current version, so a later model change has a prior version to
+    // diff against via `circuit_registry::plan_upgrade`.
+    let circuits_dir = config.proof_output_dir.join("circuits");
+    circuit_registry::register_circuit(
+        &circuits_dir,
+        &ctx.model_path,
+        &ctx.settings_path,
+        &ctx.compiled_circuit_path,
+        &ctx.pk_path,
+        &ctx.vk_path,
+    )?;
 
-    let working_dir_str = working_dir_abs.to_string_lossy().into_owned();
-    let model_path_str = model_path_abs.to_string_lossy().into_owned();
-    let srs_path_str = srs_path_abs.to_string_lossy().into_owned();
-
-    // Create a launcher bash script that calls the run_ezkl.sh shell script
-    let mut script = if Path::new(SHELL_SCRIPTS[0]).exists() {
-        format!(r#"#!/usr/bin/env bash
-set -e
-
-# First ensure the shell scripts are executable
-chmod +x {} {} {}
-
-# Check if we need to set up common resources
-if [ ! -d "shared_circuit" ] || [ ! -f "shared_circuit/model.compiled" ] || [ ! -f "shared_circuit/pk.key" ] || [ ! -f "shared_circuit/vk.key" ]; then
-    echo "Setting up common circuit resources..."
-    {} "{}" "shared_circuit" "{}"
-fi
-
-# Run the individual proof generation
-{} {} {}"#,
-            SHELL_SCRIPTS[0], SHELL_SCRIPTS[1], SHELL_SCRIPTS[2],
-            SHELL_SCRIPTS[1],
-            model_path_str,
-            srs_path_str,
-            SHELL_SCRIPTS[0],
-            if generate_contract { "--generate-contract" } else { "" },
-            working_dir_str)
-    } else {
-        return Err(anyhow::anyhow!(
-            r#"run_ezkl.sh script not found in the current directory.
-Please ensure run_ezkl.sh, run_ezkl_common.sh, and run_ezkl_individual.sh are in the working directory."#
-        ));
-    };
-    
-    script.push('\n');
-
-    fs::write(script_path, script).context("Failed to write script file")?;
-    
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        
-        // Set execute permissions on the script and shell scripts
-        let scripts_to_chmod = std::iter::once(script_path)
-            .chain(SHELL_SCRIPTS.iter().map(Path::new));
-        
-        for path in scripts_to_chmod {
-            if path.exists() {
-                let mut perms = fs::metadata(path)?.permissions();
-                perms.set_mode(0o755);
-                fs::set_permissions(path, perms).context("Failed to set permissions")?;
-            }
-        }
-    }
-    
     Ok(())
 }
-
-/// Execute the EZKL shell script and process the results
-pub fn run_ezkl_process(script_path: &Path) -> Result<(), anyhow::Error> {
-    log_status("Processing with EZKL...");
-    
-    // Execute the script with proper error handling
-    let output = Command::new(script_path)
-        .output()
-        .context("Failed to execute EZKL script")?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        
-        log_error("EZKL script execution failed");
-        log_error(&format!("stdout: {}", stdout));
-        log_error(&format!("stderr: {}", stderr));
-        
-        return Err(anyhow!("EZKL script failed with status: {}", output.status));
-    }
-    
-    // Print success output
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    log_success(&format!("EZKL script execution successful:\n{}", stdout));
-    Ok(())
-}
-
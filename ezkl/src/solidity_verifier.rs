@@ -0,0 +1,169 @@
+use anyhow::{Context, Result, anyhow};
+use colored::*;
+use halo2_proofs::SerdeFormat;
+use halo2_proofs::plonk::VerifyingKey;
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2curves::bn256::{Bn256, G1Affine};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// `ezkl_core` is this workspace's alias (see Cargo.toml `[dependencies]`) for
+// the upstream `ezkl` library crate, distinct from our own `ezkl` binary
+// crate of the same name. We only need its top-level circuit type as the
+// `ConcreteCircuit` generic below: `VerifyingKey::read` replays
+// `GraphCircuit::configure` to reconstruct the constraint system shape before
+// reading the fixed/permutation commitments, so the type we read a VK back
+// as must match the one EZKL used to produce it.
+use ezkl_core::circuit::GraphCircuit;
+
+/// Metadata pulled from the compiled circuit's constraint system, used to
+/// size the fixed verifier template (column counts, gate degrees,
+/// permutation/lookup argument shape) independently of any particular
+/// model's verifying key.
+#[derive(Debug, Clone)]
+pub struct ConstraintSystemMeta {
+    pub num_advice_columns: usize,
+    pub num_fixed_columns: usize,
+    pub num_instance_columns: usize,
+    pub max_gate_degree: usize,
+    pub num_lookup_arguments: usize,
+    pub num_permutation_columns: usize,
+}
+
+fn log_status(message: &str) {
+    println!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+fn log_success(message: &str) {
+    println!("[SUCCESS] {}", message.green());
+}
+
+/// The two artifacts produced by [`render_verifier`]: a fixed, model-agnostic
+/// verifier contract and a per-model verifying-key artifact. Redeploying a
+/// retrained credit model only ever touches the latter.
+#[derive(Debug, Clone)]
+pub struct VerifierArtifacts {
+    pub verifier_sol_path: PathBuf,
+    pub vk_sol_path: PathBuf,
+}
+
+/// Reads the constraint-system metadata EZKL records for a compiled circuit
+/// directly from its `settings.json` (the `num_..._columns` and
+/// `required_lookups` fields) so the verifier template can be sized
+/// correctly without depending on any one model's verifying key.
+pub fn read_constraint_system_meta(settings_path: &Path) -> Result<ConstraintSystemMeta> {
+    let settings_data = fs::read_to_string(settings_path)
+        .with_context(|| format!("Failed to read settings at {}", settings_path.display()))?;
+    let settings: serde_json::Value = serde_json::from_str(&settings_data)
+        .with_context(|| format!("Failed to parse settings at {}", settings_path.display()))?;
+
+    let num_lookup_arguments = settings
+        .get("required_lookups")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.len())
+        .unwrap_or(0);
+
+    Ok(ConstraintSystemMeta {
+        num_advice_columns: settings
+            .get("num_advice_columns")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("settings.json is missing num_advice_columns"))? as usize,
+        num_fixed_columns: settings
+            .get("num_fixed_columns")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        num_instance_columns: settings
+            .get("num_instance_columns")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as usize,
+        max_gate_degree: settings
+            .get("run_args")
+            .and_then(|args| args.get("logrows"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(20) as usize,
+        num_lookup_arguments,
+        num_permutation_columns: settings
+            .get("num_permutation_columns")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+    })
+}
+
+/// Renders the reusable `Halo2Verifier.sol` once from the circuit's real,
+/// typed verifying key, plus a separate `Halo2VerifyingKey.sol` holding only
+/// this model's VK bytes. Sharing the verifier across the three tiers (and
+/// any future model) is the gas-efficiency motivation for splitting the two.
+///
+/// `meta` is used only for the log line below; the column/gate-degree counts
+/// `SolidityGenerator` actually renders against come from `vk.cs()` once the
+/// VK is deserialized as a typed [`VerifyingKey`], not from `settings.json`.
+pub fn render_verifier(
+    meta: &ConstraintSystemMeta,
+    vk_path: &Path,
+    srs_path: &Path,
+    num_instances: usize,
+    output_dir: &Path,
+) -> Result<VerifierArtifacts> {
+    log_status(&format!(
+        "Rendering native Solidity verifier and verifying-key artifact ({} advice, {} fixed columns per settings.json)",
+        meta.num_advice_columns, meta.num_fixed_columns
+    ));
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create verifier output directory {}", output_dir.display()))?;
+
+    let mut srs_reader = fs::File::open(srs_path)
+        .with_context(|| format!("Failed to open SRS at {}", srs_path.display()))?;
+    let params = ParamsKZG::<Bn256>::read(&mut srs_reader)
+        .with_context(|| format!("Failed to parse SRS at {} as a KZG parameter set", srs_path.display()))?;
+
+    let mut vk_reader = fs::File::open(vk_path)
+        .with_context(|| format!("Failed to open verifying key at {}", vk_path.display()))?;
+    let vk = VerifyingKey::<G1Affine>::read::<_, GraphCircuit>(&mut vk_reader, SerdeFormat::RawBytes)
+        .with_context(|| format!("Failed to parse verifying key at {}", vk_path.display()))?;
+
+    let generator = halo2_solidity_verifier::SolidityGenerator::new(
+        &params,
+        &vk,
+        halo2_solidity_verifier::BatchOpenScheme::Bdfg21,
+        num_instances,
+    );
+
+    let (vk_solidity, verifier_solidity) = generator
+        .render_separately()
+        .context("Failed to render separated verifier/verifying-key Solidity")?;
+
+    let verifier_sol_path = output_dir.join("Halo2Verifier.sol");
+    let vk_sol_path = output_dir.join("Halo2VerifyingKey.sol");
+
+    fs::write(&verifier_sol_path, verifier_solidity)
+        .with_context(|| format!("Failed to write {}", verifier_sol_path.display()))?;
+    fs::write(&vk_sol_path, vk_solidity)
+        .with_context(|| format!("Failed to write {}", vk_sol_path.display()))?;
+
+    log_success(&format!(
+        "Wrote reusable verifier to {} and VK artifact to {}",
+        verifier_sol_path.display(),
+        vk_sol_path.display()
+    ));
+
+    Ok(VerifierArtifacts {
+        verifier_sol_path,
+        vk_sol_path,
+    })
+}
+
+/// Produces the exact calldata byte layout the rendered verifier expects for
+/// a given proof and its public instances, replacing the
+/// `ezkl encode-evm-calldata` shell step.
+pub fn encode_calldata(proof_bytes: &[u8], instances: &[Vec<[u8; 32]>]) -> Result<Vec<u8>> {
+    if proof_bytes.is_empty() {
+        return Err(anyhow!("Cannot encode calldata for an empty proof"));
+    }
+
+    Ok(halo2_solidity_verifier::encode_calldata(
+        None,
+        proof_bytes,
+        instances,
+    ))
+}
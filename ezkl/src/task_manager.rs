@@ -0,0 +1,403 @@
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::EzklConfig;
+use crate::pipeline::{Context as PipelineContext, Pipeline};
+use crate::proof_registry::create_proof_registry;
+use crate::script_generator::{create_address_input, MODEL_NAME, SRS_FILE};
+use crate::utils::address_to_filename;
+
+/// Lifecycle of a single proving job, modeled on async prover request APIs:
+/// a job is `Registered` on submit, moves to `WorkInProgress` once its worker
+/// thread starts the EZKL pipeline, and lands in exactly one terminal state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskState {
+    Registered,
+    WorkInProgress,
+    Success,
+    Failed,
+    Cancelled,
+}
+
+/// A task is keyed by the address it proves a credit score for and the
+/// model version it was submitted against, so resubmitting an address after
+/// a model upgrade doesn't collide with its previous task record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskKey {
+    pub address: String,
+    pub model_version: String,
+}
+
+/// Persisted record for a task, written next to the existing
+/// `proof_registry/<address>.json` entry so task state survives restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub address: String,
+    pub model_version: String,
+    pub state: TaskState,
+    pub submitted_at: u64,
+    pub updated_at: u64,
+    pub error: Option<String>,
+}
+
+impl TaskRecord {
+    /// Keyed by both `address` and `model_version`, matching `TaskKey`, so
+    /// resubmitting an address under a new model version doesn't overwrite
+    /// the previous version's persisted record on disk.
+    fn path(registry_dir: &str, address: &str, model_version: &str) -> PathBuf {
+        Path::new(registry_dir).join(format!(
+            "{}_{}_task.json",
+            address_to_filename(address),
+            address_to_filename(model_version)
+        ))
+    }
+
+    fn save(&self, registry_dir: &str) -> Result<()> {
+        fs::create_dir_all(registry_dir)?;
+        let path = Self::path(registry_dir, &self.address, &self.model_version);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write task record to {}", path.display()))
+    }
+
+    fn load(registry_dir: &str, address: &str, model_version: &str) -> Result<Self> {
+        let path = Self::path(registry_dir, address, model_version);
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read task record from {}", path.display()))?;
+        Ok(serde_json::from_str(&data)?)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// In-memory handle to a running task. `cancelled` is threaded into the
+/// pipeline's [`PipelineContext`], where [`crate::pipeline::utils::run_command`]
+/// polls it while a step's `ezkl` child is running and kills that child as
+/// soon as it's set, rather than only taking effect once the in-flight step
+/// happens to finish on its own.
+struct RunningTask {
+    record: Arc<Mutex<TaskRecord>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Coordinates proving jobs across addresses: submit, poll status, cancel a
+/// running job, prune old artifacts, and report aggregate statistics.
+pub struct TaskManager {
+    registry_dir: String,
+    config: EzklConfig,
+    tasks: Mutex<HashMap<TaskKey, RunningTask>>,
+    /// Held while a worker runs the shared-circuit steps of its pipeline
+    /// (not the per-address steps). `compile-circuit` and `setup` write into
+    /// the `shared_circuit/` directory that every address's pipeline shares,
+    /// so two addresses submitted close together must not run those steps
+    /// concurrently against the same `pk.key`/`vk.key`.
+    shared_circuit_lock: Arc<Mutex<()>>,
+}
+
+impl TaskManager {
+    pub fn new(registry_dir: impl Into<String>, config: EzklConfig) -> Self {
+        Self {
+            registry_dir: registry_dir.into(),
+            config,
+            tasks: Mutex::new(HashMap::new()),
+            shared_circuit_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Enqueues a proving job for `address` and spawns the EZKL pipeline
+    /// (`create_address_input` -> `run_ezkl_pipeline` -> `create_proof_registry`)
+    /// on a worker thread. Fails if a task for this `address` under ANY
+    /// `model_version` is already `Registered`/`WorkInProgress` rather than
+    /// silently replacing it: `run_pipeline`'s `address_dir` is derived from
+    /// `address` alone, so two different `model_version`s racing for the
+    /// same address would still clobber each other's `input.json`/
+    /// `witness.json`/`proof.json` even though they're distinct `TaskKey`s,
+    /// on top of orphaning the first worker's now-uncancelable `cancelled`
+    /// flag.
+    pub fn submit(&self, address: &str, model_version: &str, features: Vec<f32>) -> Result<()> {
+        let key = TaskKey {
+            address: address.to_string(),
+            model_version: model_version.to_string(),
+        };
+
+        let record = Arc::new(Mutex::new(TaskRecord {
+            address: address.to_string(),
+            model_version: model_version.to_string(),
+            state: TaskState::Registered,
+            submitted_at: now_secs(),
+            updated_at: now_secs(),
+            error: None,
+        }));
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            if let Some((existing_key, existing_state)) = tasks.iter().find_map(|(k, v)| {
+                if k.address != address {
+                    return None;
+                }
+                let state = v.record.lock().unwrap().state;
+                matches!(state, TaskState::Registered | TaskState::WorkInProgress).then_some((k, state))
+            }) {
+                return Err(anyhow!(
+                    "A task for {} @ {} is already {:?}; cancel it before resubmitting",
+                    address,
+                    existing_key.model_version,
+                    existing_state
+                ));
+            }
+            tasks.insert(
+                key.clone(),
+                RunningTask {
+                    record: record.clone(),
+                    cancelled: cancelled.clone(),
+                },
+            );
+        }
+        if let Err(err) = record.lock().unwrap().save(&self.registry_dir) {
+            // Don't leave a phantom `Registered` entry in the map with no
+            // worker thread ever spawned to advance or clean it up: a save
+            // failure here means the task never actually started.
+            self.tasks.lock().unwrap().remove(&key);
+            return Err(err);
+        }
+
+        let registry_dir = self.registry_dir.clone();
+        let config = self.config.clone();
+        let shared_circuit_lock = self.shared_circuit_lock.clone();
+        let address_owned = address.to_string();
+        thread::spawn(move || {
+            {
+                let mut rec = record.lock().unwrap();
+                rec.state = TaskState::WorkInProgress;
+                rec.updated_at = now_secs();
+                let _ = rec.save(&registry_dir);
+            }
+
+            let result = run_pipeline(&address_owned, &features, &config, &cancelled, &shared_circuit_lock);
+            let mut rec = record.lock().unwrap();
+            // A cancellation may have already set the terminal state; don't
+            // clobber it with the pipeline's own (likely killed-process) error.
+            if rec.state != TaskState::Cancelled {
+                match result {
+                    Ok(()) => rec.state = TaskState::Success,
+                    Err(e) => {
+                        rec.state = TaskState::Failed;
+                        rec.error = Some(e.to_string());
+                    }
+                }
+            }
+            rec.updated_at = now_secs();
+            let _ = rec.save(&registry_dir);
+        });
+
+        Ok(())
+    }
+
+    /// Returns the current state and elapsed time of a task.
+    pub fn status(&self, address: &str, model_version: &str) -> Result<(TaskRecord, Duration)> {
+        let key = TaskKey {
+            address: address.to_string(),
+            model_version: model_version.to_string(),
+        };
+
+        let record = if let Some(task) = self.tasks.lock().unwrap().get(&key) {
+            task.record.lock().unwrap().clone()
+        } else {
+            TaskRecord::load(&self.registry_dir, address, model_version)?
+        };
+
+        let elapsed = Duration::from_secs(now_secs().saturating_sub(record.submitted_at));
+        Ok((record, elapsed))
+    }
+
+    /// Signals a running task's worker thread to stop and marks it
+    /// `Cancelled`. No-op if the task has already reached a terminal state.
+    /// If a step is currently in flight, `run_command`'s poll loop kills
+    /// its `ezkl` child within one `POLL_INTERVAL` instead of letting it
+    /// run to completion.
+    pub fn cancel(&self, address: &str, model_version: &str) -> Result<()> {
+        let key = TaskKey {
+            address: address.to_string(),
+            model_version: model_version.to_string(),
+        };
+
+        let tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get(&key)
+            .ok_or_else(|| anyhow!("No running task found for {} @ {}", address, model_version))?;
+
+        let mut rec = task.record.lock().unwrap();
+        if matches!(rec.state, TaskState::Success | TaskState::Failed | TaskState::Cancelled) {
+            return Ok(());
+        }
+        task.cancelled.store(true, Ordering::SeqCst);
+        rec.state = TaskState::Cancelled;
+        rec.updated_at = now_secs();
+        rec.save(&self.registry_dir)?;
+
+        Ok(())
+    }
+
+    /// Deletes artifacts for tasks that finished more than `ttl` ago. Since
+    /// `address_dir` is shared by every `model_version` submitted for an
+    /// address (it's derived from `address` alone), it's only safe to remove
+    /// once every task record for that address is stale and terminal —
+    /// otherwise this would delete a still-running model_version's
+    /// `input.json`/`witness.json`/`proof.json` out from under it. Per-record
+    /// files that qualify individually are still removed either way.
+    pub fn prune(&self, ttl: Duration) -> Result<Vec<String>> {
+        let mut pruned = Vec::new();
+        let cutoff = now_secs().saturating_sub(ttl.as_secs());
+
+        let entries = match fs::read_dir(&self.registry_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(pruned),
+        };
+
+        let mut records_by_address: HashMap<String, Vec<(PathBuf, TaskRecord)>> = HashMap::new();
+        for entry in entries {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !file_name.ends_with("_task.json") {
+                continue;
+            }
+
+            let data = fs::read_to_string(entry.path())?;
+            let record: TaskRecord = match serde_json::from_str(&data) {
+                Ok(record) => record,
+                Err(_) => continue,
+            };
+
+            records_by_address
+                .entry(record.address.clone())
+                .or_default()
+                .push((entry.path(), record));
+        }
+
+        for (address, records) in records_by_address {
+            let is_stale = |record: &TaskRecord| {
+                record.updated_at < cutoff
+                    && matches!(record.state, TaskState::Success | TaskState::Failed | TaskState::Cancelled)
+            };
+            let all_stale = records.iter().all(|(_, record)| is_stale(record));
+
+            if all_stale {
+                let address_dir = self.config.proof_output_dir.join(address_to_filename(&address));
+                if address_dir.exists() {
+                    fs::remove_dir_all(&address_dir)
+                        .with_context(|| format!("Failed to prune artifacts at {}", address_dir.display()))?;
+                }
+                for (path, _) in &records {
+                    fs::remove_file(path)?;
+                }
+                pruned.push(address);
+            } else {
+                for (path, record) in &records {
+                    if is_stale(record) {
+                        fs::remove_file(path)?;
+                    }
+                }
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Aggregates task counts and timings across every registry entry.
+    pub fn report(&self) -> Result<TaskReport> {
+        let mut report = TaskReport::default();
+
+        let entries = match fs::read_dir(&self.registry_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(report),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !file_name.ends_with("_task.json") {
+                continue;
+            }
+
+            let data = fs::read_to_string(entry.path())?;
+            let record: TaskRecord = match serde_json::from_str(&data) {
+                Ok(record) => record,
+                Err(_) => continue,
+            };
+
+            report.total += 1;
+            match record.state {
+                TaskState::Registered => report.registered += 1,
+                TaskState::WorkInProgress => report.in_progress += 1,
+                TaskState::Success => report.succeeded += 1,
+                TaskState::Failed => report.failed += 1,
+                TaskState::Cancelled => report.cancelled += 1,
+            }
+            report.total_duration_secs += record.updated_at.saturating_sub(record.submitted_at);
+        }
+
+        Ok(report)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TaskReport {
+    pub total: u64,
+    pub registered: u64,
+    pub in_progress: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    pub total_duration_secs: u64,
+}
+
+fn run_pipeline(
+    address: &str,
+    features: &[f32],
+    config: &EzklConfig,
+    cancelled: &Arc<AtomicBool>,
+    shared_circuit_lock: &Arc<Mutex<()>>,
+) -> Result<()> {
+    let address_dir = config.proof_output_dir.join(address_to_filename(address));
+    fs::create_dir_all(&address_dir)?;
+
+    create_address_input(features, address, &address_dir.to_string_lossy())?;
+
+    let model_path = config.proof_output_dir.join(MODEL_NAME);
+    let srs_path = config.proof_output_dir.join(SRS_FILE);
+    let ctx = PipelineContext::new(config, &address_dir, &model_path, &srs_path, config.generate_evm_verifier)?
+        .with_cancellation(cancelled.clone());
+
+    if cancelled.load(Ordering::SeqCst) {
+        return Err(anyhow!("EZKL pipeline for {} was cancelled", address));
+    }
+
+    // `compile-circuit`/`setup` (and, if enabled, `create-evm-verifier`) only
+    // write into the shared `shared_circuit/` directory, so only those are
+    // serialized: `run` consults `Step::is_complete` so addresses that find
+    // the circuit (or contract) already built just skip straight past them,
+    // but two addresses racing to build it for the first time must not run
+    // them against the same `pk.key`/`vk.key` at once. The per-address steps
+    // below touch only this address's own witness/proof files, so they run
+    // outside the lock and don't block other addresses' proving work.
+    {
+        let _shared_circuit_guard = shared_circuit_lock.lock().unwrap();
+        Pipeline::shared_circuit_steps(config.generate_evm_verifier).run(&ctx)?;
+    }
+    Pipeline::per_address_steps().run(&ctx)?;
+
+    create_proof_registry(address, &address_dir.to_string_lossy())?;
+    Ok(())
+}
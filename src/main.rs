@@ -1,6 +1,16 @@
 use std::path::PathBuf;
+use std::time::Duration;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+
+use ezkl::config;
+use ezkl::task_manager::TaskManager;
+use ezkl::utils::get_features_for_address;
+use synthetic_data::generate_synthetic_data_with_test_addresses;
+
+const TASK_REGISTRY_DIR: &str = "proof_registry";
+const DEFAULT_MODEL_VERSION: &str = "1.0.0";
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
@@ -20,6 +30,29 @@ enum Commands {
         #[arg(short, long)]
         list: bool,
     },
+    /// Submit a proving job for the address
+    Submit {
+        /// Model version this task is submitted against
+        #[arg(long, default_value = DEFAULT_MODEL_VERSION)]
+        model_version: String,
+    },
+    /// Poll the state of a previously submitted proving job
+    Status {
+        #[arg(long, default_value = DEFAULT_MODEL_VERSION)]
+        model_version: String,
+    },
+    /// Cancel a running proving job
+    Cancel {
+        #[arg(long, default_value = DEFAULT_MODEL_VERSION)]
+        model_version: String,
+    },
+    /// Delete artifacts for tasks older than the given number of hours
+    Prune {
+        #[arg(long, default_value_t = 24)]
+        ttl_hours: u64,
+    },
+    /// Print aggregate counts and timings across all registered tasks
+    Report,
 }
 
 fn main() -> Result<()> {
@@ -31,11 +64,43 @@ fn main() -> Result<()> {
         println!("Value for config: {}", config_path.display());
     }
 
-    if let Some(Commands::Test { list }) = cli.command {
-        if list {
-            // Here you would implement actual test listing functionality
-            println!("Test command executed with list flag enabled");
+    let ezkl_config = config::resolve_with_override(cli.config.as_deref())?;
+    let manager = TaskManager::new(TASK_REGISTRY_DIR, ezkl_config);
+
+    match cli.command {
+        Some(Commands::Test { list }) => {
+            if list {
+                // Here you would implement actual test listing functionality
+                println!("Test command executed with list flag enabled");
+            }
+        }
+        Some(Commands::Submit { model_version }) => {
+            let data = generate_synthetic_data_with_test_addresses(1000)?;
+            let features = get_features_for_address(&data, &cli.address)?;
+            manager.submit(&cli.address, &model_version, features)?;
+            println!("Submitted proving task for {} @ {}", cli.address, model_version);
+        }
+        Some(Commands::Status { model_version }) => {
+            let (record, elapsed) = manager.status(&cli.address, &model_version)?;
+            println!("State: {:?}", record.state);
+            println!("Elapsed: {}s", elapsed.as_secs());
+            if let Some(error) = record.error {
+                println!("Error: {}", error);
+            }
+        }
+        Some(Commands::Cancel { model_version }) => {
+            manager.cancel(&cli.address, &model_version)?;
+            println!("Cancelled proving task for {} @ {}", cli.address, model_version);
+        }
+        Some(Commands::Prune { ttl_hours }) => {
+            let pruned = manager.prune(Duration::from_secs(ttl_hours * 3600))?;
+            println!("Pruned {} task(s): {:?}", pruned.len(), pruned);
+        }
+        Some(Commands::Report) => {
+            let report = manager.report()?;
+            println!("{:#?}", report);
         }
+        None => {}
     }
 
     Ok(())